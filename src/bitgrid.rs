@@ -0,0 +1,332 @@
+use core::fmt;
+use std::ops::{BitAnd, BitOr, BitXor};
+
+use crate::grid::{Direction, Grid, Position};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// one bit per cell, every row starts at a fresh word and unused bits of the last word are always zero
+#[derive(Debug, PartialEq, Eq, Clone, Default, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    pub columns: usize,
+    rows: usize,
+}
+
+impl BitGrid {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        BitGrid {
+            words: vec![0; columns.div_ceil(WORD_BITS) * rows],
+            columns,
+            rows,
+        }
+    }
+
+    pub fn from_grid<T, P: FnMut(&T) -> bool>(grid: &Grid<T>, predicate: P) -> Self {
+        let mut bits = BitGrid::new(grid.columns, grid.rows());
+        grid.find_positions(predicate)
+            .for_each(|p| bits.set(&p, true));
+        bits
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn words_per_row(&self) -> usize {
+        self.columns.div_ceil(WORD_BITS)
+    }
+
+    // mask for the valid bits of the last word in a row
+    fn last_word_mask(&self) -> u64 {
+        match self.columns % WORD_BITS {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    fn word_index(&self, pos: &Position) -> Option<(usize, u64)> {
+        if pos.x >= self.columns || pos.y >= self.rows {
+            return None;
+        }
+        let word = pos.y * self.words_per_row() + pos.x / WORD_BITS;
+        Some((word, 1 << (pos.x % WORD_BITS)))
+    }
+
+    pub fn get(&self, pos: &Position) -> Option<bool> {
+        let (word, bit) = self.word_index(pos)?;
+        Some(self.words[word] & bit != 0)
+    }
+
+    pub fn set(&mut self, pos: &Position, value: bool) {
+        let (word, bit) = self.word_index(pos).expect("Out of bound position");
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.rows).flat_map(move |y| {
+            (0..self.columns)
+                .map(move |x| Position { x, y })
+                .filter(|p| self.get(p) == Some(true))
+        })
+    }
+
+    // the bitmask of a row, bit `x % 64` of word `x / 64` is the cell in column `x`
+    pub fn row(&self, row_index: usize) -> &[u64] {
+        if row_index >= self.rows {
+            panic!("Out of bound row index");
+        }
+        let words = self.words_per_row();
+        &self.words[row_index * words..(row_index + 1) * words]
+    }
+
+    pub fn row_count_ones(&self, row_index: usize) -> usize {
+        self.row(row_index)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn row_xor(&self, a: usize, b: usize) -> impl Iterator<Item = u64> + '_ {
+        self.row(a).iter().zip(self.row(b)).map(|(a, b)| a ^ b)
+    }
+
+    pub fn column_xor(&self, a: usize, b: usize) -> impl Iterator<Item = bool> + '_ {
+        if a >= self.columns || b >= self.columns {
+            panic!("Out of bound column index");
+        }
+        (0..self.rows)
+            .map(move |y| self.get(&Position { x: a, y }) != self.get(&Position { x: b, y }))
+    }
+
+    pub fn row_hamming(&self, a: usize, b: usize) -> usize {
+        self.row_xor(a, b).map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn column_hamming(&self, a: usize, b: usize) -> usize {
+        self.column_xor(a, b).filter(|d| *d).count()
+    }
+
+    pub fn hamming(&self, other: &BitGrid) -> usize {
+        (self ^ other).count_ones()
+    }
+
+    pub fn transposed(&self) -> BitGrid {
+        let mut bits = BitGrid::new(self.rows, self.columns);
+        self.iter_ones()
+            .for_each(|p| bits.set(&Position { x: p.y, y: p.x }, true));
+        bits
+    }
+
+    // cells moved over the edge are dropped, cells moving in are unset
+    pub fn shift(&mut self, dir: Direction, n: usize) {
+        let words = self.words_per_row();
+        match dir {
+            Direction::North => {
+                let n = n.min(self.rows);
+                self.words.drain(..n * words);
+                self.words.resize(self.rows * words, 0);
+            }
+            Direction::South => {
+                let n = n.min(self.rows);
+                self.words.truncate((self.rows - n) * words);
+                self.words.splice(0..0, std::iter::repeat_n(0, n * words));
+            }
+            Direction::East => {
+                let mask = self.last_word_mask();
+                self.words.chunks_mut(words).for_each(|row| {
+                    shift_row_up(row, n);
+                    if let Some(last) = row.last_mut() {
+                        *last &= mask;
+                    }
+                });
+            }
+            Direction::West => {
+                self.words
+                    .chunks_mut(words)
+                    .for_each(|row| shift_row_down(row, n));
+            }
+        }
+    }
+
+    pub fn shifted(&self, dir: Direction, n: usize) -> BitGrid {
+        let mut bits = self.clone();
+        bits.shift(dir, n);
+        bits
+    }
+
+    fn zip_words<F: Fn(u64, u64) -> u64>(&self, other: &BitGrid, f: F) -> BitGrid {
+        if self.columns != other.columns || self.rows != other.rows {
+            panic!("Mismatching grid sizes");
+        }
+        BitGrid {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| f(*a, *b))
+                .collect(),
+            columns: self.columns,
+            rows: self.rows,
+        }
+    }
+}
+
+// moves every bit of the row `n` places towards the higher bits
+fn shift_row_up(row: &mut [u64], n: usize) {
+    let (words, bits) = (n / WORD_BITS, n % WORD_BITS);
+    for i in (0..row.len()).rev() {
+        let low = i.checked_sub(words).map_or(0, |j| row[j]);
+        let carry = i
+            .checked_sub(words + 1)
+            .filter(|_| bits > 0)
+            .map_or(0, |j| row[j] >> (WORD_BITS - bits));
+        row[i] = (low << bits) | carry;
+    }
+}
+
+// moves every bit of the row `n` places towards the lower bits
+fn shift_row_down(row: &mut [u64], n: usize) {
+    let (words, bits) = (n / WORD_BITS, n % WORD_BITS);
+    for i in 0..row.len() {
+        let high = row.get(i + words).copied().unwrap_or(0);
+        let carry = row
+            .get(i + words + 1)
+            .filter(|_| bits > 0)
+            .map_or(0, |w| w << (WORD_BITS - bits));
+        row[i] = (high >> bits) | carry;
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        self.zip_words(rhs, |a, b| a ^ b)
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.zip_words(rhs, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.zip_words(rhs, |a, b| a | b)
+    }
+}
+
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                let set = self.get(&Position { x, y }) == Some(true);
+                write!(f, "{}", if set { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn bit_grid(s: &str) -> BitGrid {
+        let grid: Grid<char> = Grid::from_str(s).unwrap();
+        BitGrid::from_grid(&grid, |c| c == &'#')
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let bits = bit_grid("#..#\n.##.\n....\n");
+        assert_eq!(bits.columns, 4);
+        assert_eq!(bits.rows(), 3);
+        assert_eq!(bits.count_ones(), 4);
+        assert_eq!(bits.get(&Position { x: 3, y: 0 }), Some(true));
+        assert_eq!(bits.get(&Position { x: 0, y: 1 }), Some(false));
+        assert_eq!(bits.get(&Position { x: 4, y: 0 }), None);
+        assert_eq!(bits.row(1), &[0b0110]);
+        assert_eq!(bits.to_string(), "#..#\n.##.\n....\n");
+    }
+
+    #[test]
+    fn test_hamming() {
+        let bits = bit_grid("#.##\n#..#\n##..\n");
+        assert_eq!(bits.row_hamming(0, 1), 1);
+        assert_eq!(bits.row_hamming(1, 2), 2);
+        assert_eq!(bits.row_xor(0, 2).collect::<Vec<_>>(), vec![0b1110]);
+        assert_eq!(bits.column_hamming(0, 3), 1);
+        assert_eq!(
+            bits.column_xor(1, 2).collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+
+        let other = bit_grid("#.##\n#..#\n#...\n");
+        assert_eq!(bits.hamming(&other), 1);
+        assert_eq!((&bits ^ &other).to_string(), "....\n....\n.#..\n");
+        assert_eq!((&bits & &other).count_ones(), 6);
+        assert_eq!((&bits | &other).count_ones(), 7);
+    }
+
+    #[test]
+    fn test_transposed() {
+        let bits = bit_grid("#..\n.##\n");
+        assert_eq!(bits.transposed().to_string(), "#.\n.#\n.#\n");
+    }
+
+    #[test]
+    fn test_shift() {
+        let bits = bit_grid("#..#\n.##.\n...#\n");
+        assert_eq!(
+            bits.shifted(Direction::East, 1).to_string(),
+            ".#..\n..##\n....\n"
+        );
+        assert_eq!(
+            bits.shifted(Direction::West, 2).to_string(),
+            ".#..\n#...\n.#..\n"
+        );
+        assert_eq!(
+            bits.shifted(Direction::North, 1).to_string(),
+            ".##.\n...#\n....\n"
+        );
+        assert_eq!(
+            bits.shifted(Direction::South, 2).to_string(),
+            "....\n....\n#..#\n"
+        );
+        assert_eq!(bits.shifted(Direction::South, 5).count_ones(), 0);
+    }
+
+    #[test]
+    fn test_shift_wide() {
+        let mut bits = BitGrid::new(130, 1);
+        bits.set(&Position { x: 0, y: 0 }, true);
+        bits.set(&Position { x: 63, y: 0 }, true);
+        bits.set(&Position { x: 129, y: 0 }, true);
+
+        let east = bits.shifted(Direction::East, 65);
+        assert_eq!(
+            east.iter_ones().map(|p| p.x).collect::<Vec<_>>(),
+            vec![65, 128]
+        );
+
+        let west = bits.shifted(Direction::West, 63);
+        assert_eq!(
+            west.iter_ones().map(|p| p.x).collect::<Vec<_>>(),
+            vec![0, 66]
+        );
+    }
+}
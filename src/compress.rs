@@ -0,0 +1,128 @@
+use crate::grid::{Grid, IntPosition, Position};
+
+// every compressed cell stands for the rectangle of original cells between two neighbouring breakpoints
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Compression {
+    xs: Vec<isize>,
+    ys: Vec<isize>,
+}
+
+fn breakpoints<I: IntoIterator<Item = isize>>(coordinates: I) -> Vec<isize> {
+    let mut breakpoints = coordinates
+        .into_iter()
+        .flat_map(|c| [c, c + 1])
+        .collect::<Vec<_>>();
+    breakpoints.sort_unstable();
+    breakpoints.dedup();
+    if let (Some(&min), Some(&max)) = (breakpoints.first(), breakpoints.last()) {
+        breakpoints.insert(0, min - 1);
+        breakpoints.push(max + 1);
+    }
+    breakpoints
+}
+
+impl Compression {
+    // every point keeps a compressed cell of its own and a one cell wide margin is kept around all of them
+    pub fn new<I: IntoIterator<Item = IntPosition>>(points: I) -> Self {
+        let points = points.into_iter().collect::<Vec<_>>();
+        Compression {
+            xs: breakpoints(points.iter().map(|p| p.x)),
+            ys: breakpoints(points.iter().map(|p| p.y)),
+        }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.xs.len().saturating_sub(1)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.ys.len().saturating_sub(1)
+    }
+
+    pub fn compress(&self, pos: &IntPosition) -> Option<Position> {
+        let index = |breakpoints: &[isize], c: isize| {
+            let i = breakpoints.partition_point(|b| *b <= c);
+            (i > 0 && i < breakpoints.len()).then(|| i - 1)
+        };
+        Some(Position {
+            x: index(&self.xs, pos.x)?,
+            y: index(&self.ys, pos.y)?,
+        })
+    }
+
+    // the top left original cell covered by the compressed cell
+    pub fn decompress(&self, pos: &Position) -> IntPosition {
+        IntPosition {
+            x: self.xs[pos.x],
+            y: self.ys[pos.y],
+        }
+    }
+
+    pub fn cell_size(&self, pos: &Position) -> (usize, usize) {
+        (
+            (self.xs[pos.x + 1] - self.xs[pos.x]) as usize,
+            (self.ys[pos.y + 1] - self.ys[pos.y]) as usize,
+        )
+    }
+
+    pub fn weight(&self, pos: &Position) -> usize {
+        let (width, height) = self.cell_size(pos);
+        width * height
+    }
+
+    pub fn grid<T: Clone>(&self, value: T) -> Grid<T> {
+        Grid::filled(self.columns(), self.rows(), value)
+    }
+
+    pub fn weights(&self) -> Grid<usize> {
+        Grid::from_fn(self.columns(), self.rows(), |p| self.weight(&p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression() {
+        let compression = Compression::new([
+            IntPosition { x: -5, y: 0 },
+            IntPosition { x: 100, y: 0 },
+            IntPosition { x: 100, y: 1000 },
+        ]);
+
+        // -6 | -5 | -4..99 | 100 | 101
+        assert_eq!(compression.columns(), 5);
+        // -1 | 0 | 1..999 | 1000 | 1001
+        assert_eq!(compression.rows(), 5);
+
+        assert_eq!(
+            compression.compress(&IntPosition { x: 100, y: 0 }),
+            Some(Position { x: 3, y: 1 })
+        );
+        assert_eq!(
+            compression.compress(&IntPosition { x: 50, y: 999 }),
+            Some(Position { x: 2, y: 2 })
+        );
+        assert_eq!(compression.compress(&IntPosition { x: -7, y: 0 }), None);
+        assert_eq!(compression.compress(&IntPosition { x: 0, y: 1002 }), None);
+
+        assert_eq!(
+            compression.decompress(&Position { x: 2, y: 2 }),
+            IntPosition { x: -4, y: 1 }
+        );
+        assert_eq!(compression.cell_size(&Position { x: 2, y: 2 }), (104, 999));
+        assert_eq!(compression.weight(&Position { x: 3, y: 1 }), 1);
+
+        let weights = compression.weights();
+        assert_eq!(weights.iter().sum::<usize>(), 108 * 1003);
+    }
+
+    #[test]
+    fn test_compression_empty() {
+        let compression = Compression::new([]);
+        assert_eq!(compression.columns(), 0);
+        assert_eq!(compression.rows(), 0);
+        assert_eq!(compression.compress(&IntPosition { x: 0, y: 0 }), None);
+    }
+}
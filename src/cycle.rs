@@ -0,0 +1,138 @@
+use std::{collections::HashMap, hash::Hash};
+
+// the states from step `tail` on repeat every `period` steps
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub tail: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    // earliest step with the same state as step `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.period
+        }
+    }
+}
+
+pub fn floyd<S: Clone + PartialEq, F: FnMut(&S) -> S>(start: S, mut f: F) -> Cycle {
+    let mut tortoise = f(&start);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        hare = f(&hare);
+    }
+
+    let mut tail = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        tail += 1;
+    }
+
+    let mut period = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        period += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+pub fn brent<S: Clone + PartialEq, F: FnMut(&S) -> S>(start: S, mut f: F) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = f(&hare);
+        period += 1;
+    }
+
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..period {
+        hare = f(&hare);
+    }
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        tail += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+// keeps every state, so `f` is only called `tail + period` times, the returned states are steps 0..tail + period
+pub fn detect<S: Clone + Eq + Hash, F: FnMut(&S) -> S>(start: S, mut f: F) -> (Cycle, Vec<S>) {
+    let mut seen = HashMap::from([(start.clone(), 0)]);
+    let mut states = vec![start];
+    loop {
+        let next = f(states.last().unwrap());
+        if let Some(&tail) = seen.get(&next) {
+            let period = states.len() - tail;
+            return (Cycle { tail, period }, states);
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+}
+
+// only keeps a couple of states alive, in exchange `f` is evaluated a few more times than with `detect`
+pub fn state_at<S: Clone + PartialEq, F: FnMut(&S) -> S>(start: S, mut f: F, n: usize) -> S {
+    let cycle = brent(start.clone(), &mut f);
+    (0..cycle.reduce(n)).fold(start, |s, _| f(&s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 1 2 3 4 5 6 | 7 8 9 10 11 | 7 ...
+    fn step(n: &usize) -> usize {
+        if *n == 11 {
+            7
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_floyd() {
+        assert_eq!(floyd(0, step), Cycle { tail: 7, period: 5 });
+        assert_eq!(floyd(9, step), Cycle { tail: 0, period: 5 });
+    }
+
+    #[test]
+    fn test_brent() {
+        assert_eq!(brent(0, step), Cycle { tail: 7, period: 5 });
+        assert_eq!(brent(3, |_| 3), Cycle { tail: 0, period: 1 });
+    }
+
+    #[test]
+    fn test_detect() {
+        let (cycle, states) = detect(0, step);
+        assert_eq!(cycle, Cycle { tail: 7, period: 5 });
+        assert_eq!(states, (0..12).collect::<Vec<_>>());
+        assert_eq!(states[cycle.reduce(1_000_000_000)], 10);
+    }
+
+    #[test]
+    fn test_state_at() {
+        assert_eq!(state_at(0, step, 3), 3);
+        assert_eq!(state_at(0, step, 12), 7);
+        assert_eq!(state_at(0, step, 1_000_000_000), 10);
+    }
+}
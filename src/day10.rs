@@ -1,31 +1,9 @@
-use std::{cmp::Ordering, str::FromStr};
-
-use crate::grid::{Grid, Position};
-
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-pub enum Direction {
-    North = 0,
-    West,
-    South,
-    East,
-}
-impl Direction {
-    fn invert(&self) -> Direction {
-        match self {
-            Direction::North => Direction::South,
-            Direction::East => Direction::West,
-            Direction::South => Direction::North,
-            Direction::West => Direction::East,
-        }
-    }
-}
+use std::{collections::HashSet, str::FromStr};
+
+use itertools::Itertools;
 
-const DIRECTIONS: [Direction; 4] = [
-    Direction::North,
-    Direction::West,
-    Direction::South,
-    Direction::East,
-];
+use crate::grid::{Direction, Grid, Position};
+use crate::render::{Color, Style};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Pipe(Direction, Direction);
@@ -44,6 +22,10 @@ impl Pipe {
             _ => false,
         }
     }
+    fn connects(&self, a: Direction, b: Direction) -> bool {
+        self.has_entry_in_direction(a) && self.has_entry_in_direction(b)
+    }
+
     fn get_exit_direction(&self, enter_direction: &Direction) -> Option<Direction> {
         if !self.is_accessible_from_direction(enter_direction) {
             return None;
@@ -57,6 +39,23 @@ impl Pipe {
     }
 }
 
+impl From<&Pipe> for char {
+    fn from(value: &Pipe) -> Self {
+        use Direction::*;
+        [
+            ('|', North, South),
+            ('-', East, West),
+            ('L', North, East),
+            ('J', North, West),
+            ('7', South, West),
+            ('F', South, East),
+        ]
+        .into_iter()
+        .find(|(_, a, b)| value.connects(*a, *b))
+        .map_or('?', |(c, _, _)| c)
+    }
+}
+
 #[derive(Debug)]
 pub enum ParseError {
     InvalidPipe,
@@ -107,75 +106,120 @@ struct PipeIterator<'a> {
 }
 
 impl<'a> PipeIterator<'a> {
-    fn new(grid: &'a Grid<Tile>, start_pos: Position) -> Self {
+    fn new(grid: &'a Grid<Tile>, start_pos: Position, direction: Direction) -> Self {
         Self {
             grid,
             current_pos: start_pos,
-            next_direction: None,
-        }
-    }
-
-    fn pos_in_direction(&self, dir: &Direction) -> Option<Position> {
-        match dir {
-            Direction::South => Some(Position {
-                x: self.current_pos.x,
-                y: self.current_pos.y + 1,
-            }),
-            Direction::East => Some(Position {
-                x: self.current_pos.x + 1,
-                y: self.current_pos.y,
-            }),
-            Direction::North => Some(Position {
-                x: self.current_pos.x,
-                y: self.current_pos.y.checked_sub(1)?,
-            }),
-            Direction::West => Some(Position {
-                x: self.current_pos.x.checked_sub(1)?,
-                y: self.current_pos.y,
-            }),
+            next_direction: Some(direction),
         }
     }
 }
+
+// yields every pipe after the start together with its position, ends at the first tile which can't be entered
 impl<'a> Iterator for PipeIterator<'a> {
-    type Item = &'a Tile;
+    type Item = (Position, &'a Tile);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let directions_to_check = self
-            .next_direction
-            .map(|d| vec![d])
-            .unwrap_or(DIRECTIONS.to_vec());
-
-        let positions = directions_to_check
-            .iter()
-            .map(|d| (d, self.pos_in_direction(d)))
-            .filter_map(|(d, p)| p.and_then(|p| self.grid.is_position_valid(&p).then_some((d, p))));
-
-        let tiles = positions
-            // .map(|(d, p)| (d, p, self.grid.at(&p).unwrap()))
-            .filter_map(|(d, p)| self.grid.at(&p).map(|t| (d, p, t)))
-            .collect::<Vec<_>>();
-
-        tiles.into_iter().find_map(|(d, p, t)| match t {
-            Tile::Pipe(pipe) if pipe.is_accessible_from_direction(d) => {
+        let d = self.next_direction?;
+        let p = self.grid.step(&self.current_pos, &d)?;
+        let t = &self.grid[p];
+        match t {
+            Tile::Pipe(pipe) if pipe.is_accessible_from_direction(&d) => {
                 self.current_pos = p;
-                self.next_direction = pipe.get_exit_direction(d);
-                Some(t)
+                self.next_direction = pipe.get_exit_direction(&d);
+                Some((p, t))
             }
             _ => None,
-        })
+        }
     }
 }
 
+struct PipeLoop {
+    // starts with the start position
+    positions: Vec<Position>,
+    start_pipe: Pipe,
+}
+
+// follows the pipes from the start in every direction until one walk gets back to it,
+// the start pipe connects the first step with the step back into the start
+fn find_loop(grid: &Grid<Tile>) -> Option<PipeLoop> {
+    let start_pos = grid.find_pos(|t| t == &Tile::Start)?;
+    Direction::all().into_iter().find_map(|first| {
+        let mut pipes = PipeIterator::new(grid, start_pos, first);
+        let positions = std::iter::once(start_pos)
+            .chain(pipes.by_ref().map(|(p, _)| p))
+            .collect::<Vec<_>>();
+        let last = pipes.next_direction?;
+        (positions.len() > 1 && grid.step(&pipes.current_pos, &last) == Some(start_pos)).then(
+            || PipeLoop {
+                positions,
+                start_pipe: Pipe(first, last.invert()),
+            },
+        )
+    })
+}
+
 #[aoc(day10, part1)]
 pub fn part1(input: &Grid<Tile>) -> usize {
-    let start_pos = input.find_pos(|t| t == &Tile::Start).unwrap();
-    let pipes = PipeIterator::new(input, start_pos).collect::<Vec<_>>();
-    (pipes.len() + 1) / 2
+    let pipe_loop = find_loop(input).expect("No loop through the start");
+    pipe_loop.positions.len() / 2
+}
+
+// loop pipes keep their symbol, every other tile becomes `I` or `O` by the parity of the north facing pipes left of it
+pub fn enclosed_map(grid: &Grid<Tile>) -> Grid<char> {
+    let pipe_loop = find_loop(grid).expect("No loop through the start");
+    let on_loop = pipe_loop.positions.iter().collect::<HashSet<_>>();
+
+    let mut inside = false;
+    grid.map_with_pos(|p, _| {
+        if p.x == 0 {
+            inside = false;
+        }
+        if on_loop.contains(&p) {
+            let pipe = match &grid[p] {
+                Tile::Pipe(pipe) => pipe,
+                _ => &pipe_loop.start_pipe,
+            };
+            inside ^= pipe.has_entry_in_direction(Direction::North);
+            char::from(pipe)
+        } else if inside {
+            'I'
+        } else {
+            'O'
+        }
+    })
 }
 
+pub fn render_enclosed(grid: &Grid<Tile>) -> String {
+    let map = enclosed_map(grid);
+    let rendered = map
+        .render()
+        .style(|_, c| match c {
+            'I' => Style::bg(Color::Green),
+            'O' => Style::default(),
+            _ => Style::fg(Color::Yellow).bold(),
+        })
+        .to_string();
+    rendered
+}
+
+// shoelace formula for the area of the loop, Pick's theorem gives the tiles strictly inside of it
 #[aoc(day10, part2)]
 pub fn part2(input: &Grid<Tile>) -> usize {
-    0
+    let loop_positions = find_loop(input)
+        .expect("No loop through the start")
+        .positions;
+    let double_area = loop_positions
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| (a.x * b.y) as isize - (b.x * a.y) as isize)
+        .sum::<isize>()
+        .unsigned_abs();
+
+    (double_area + 2)
+        .checked_sub(loop_positions.len())
+        .expect("Loop area is too small for its length")
+        / 2
 }
 
 #[cfg(test)]
@@ -206,4 +250,82 @@ LJ.LJ";
     fn test_part2() {
         assert_eq!(1, part2(&parse(TEST_INPUT)));
     }
+
+    const TEST_INPUT_ENCLOSED: &str = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+    const TEST_INPUT_LARGER: &str = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+
+    #[test]
+    fn test_infer_start_pipe() {
+        let grid = parse(TEST_INPUT);
+        assert_eq!(
+            find_loop(&grid).map(|l| char::from(&l.start_pipe)),
+            Some('F')
+        );
+
+        let grid = parse(TEST_INPUT_ENCLOSED);
+        assert_eq!(
+            find_loop(&grid).map(|l| char::from(&l.start_pipe)),
+            Some('F')
+        );
+        assert_eq!(find_loop(&grid).unwrap().positions.len(), 46);
+
+        assert!(find_loop(&parse(".S-7.\n.|.|.\n...J.\n")).is_none());
+    }
+
+    const TEST_INPUT_STRAY_PIPE: &str = ".|...
+.S-7.
+.|.|.
+.L-J.
+.....";
+
+    #[test]
+    fn test_stray_pipe_into_start() {
+        let grid = parse(TEST_INPUT_STRAY_PIPE);
+        assert_eq!(
+            find_loop(&grid).map(|l| char::from(&l.start_pipe)),
+            Some('F')
+        );
+        assert_eq!(4, part1(&grid));
+        assert_eq!(1, part2(&grid));
+        assert_eq!(enclosed_map(&grid).row(2), &['O', '|', 'I', '|', 'O']);
+    }
+
+    #[test]
+    fn test_part2_enclosed() {
+        assert_eq!(4, part2(&parse(TEST_INPUT_ENCLOSED)));
+        assert_eq!(8, part2(&parse(TEST_INPUT_LARGER)));
+    }
+
+    #[test]
+    fn test_enclosed_map() {
+        let map = enclosed_map(&parse(TEST_INPUT_ENCLOSED));
+        assert_eq!(
+            map.row(6),
+            &['O', '|', 'I', 'I', '|', 'O', '|', 'I', 'I', '|', 'O']
+        );
+
+        for input in [TEST_INPUT, TEST_INPUT_ENCLOSED, TEST_INPUT_LARGER] {
+            let grid = parse(input);
+            let inside = enclosed_map(&grid).iter().filter(|c| c == &&'I').count();
+            assert_eq!(inside, part2(&grid));
+        }
+    }
 }
@@ -66,7 +66,7 @@ fn expand_universe(
         .enumerate()
         .filter_map(|(i, v)| {
             v.iter()
-                .all(|e| e == &&GalaxyMapItem::EmptySpace)
+                .all(|e| e == &GalaxyMapItem::EmptySpace)
                 .then_some(i)
         })
         .collect::<Vec<_>>();
@@ -75,24 +75,26 @@ fn expand_universe(
         .enumerate()
         .filter_map(|(i, v)| {
             v.iter()
-                .all(|e| e == &&GalaxyMapItem::EmptySpace)
+                .all(|e| e == &GalaxyMapItem::EmptySpace)
                 .then_some(i)
         })
         .collect::<Vec<_>>();
 
-    empty_rows.iter().rev().for_each(|r| {
-        universe.insert_row_with(*r, expand_item);
-        if expand_item == &GalaxyMapItem::Void {
-            universe.drop_row(*r + 1);
-        }
-    });
-
-    empty_cols.iter().rev().for_each(|c| {
-        universe.insert_col_with(*c, expand_item);
-        if expand_item == &GalaxyMapItem::Void {
-            universe.drop_column(*c + 1);
-        }
-    });
+    if expand_item == &GalaxyMapItem::Void {
+        empty_rows.iter().for_each(|r| {
+            universe
+                .iter_row_mut(*r)
+                .for_each(|e| *e = GalaxyMapItem::Void)
+        });
+        empty_cols.iter().for_each(|c| {
+            universe
+                .iter_col_mut(*c)
+                .for_each(|e| *e = GalaxyMapItem::Void)
+        });
+    } else {
+        universe.insert_rows_with(&empty_rows, expand_item);
+        universe.insert_cols_with(&empty_cols, expand_item);
+    }
 
     universe
 }
@@ -124,10 +126,8 @@ pub fn part2(input: &Grid<GalaxyMapItem>) -> usize {
             let y_min = p[0].y.min(p[1].y);
             let y_max = p[0].y.max(p[1].y);
 
-            let x_fields =
-                (x_min + 1..=x_max).map(|x| universe.at(&Position { x, y: y_min }).unwrap());
-            let y_fields =
-                (y_min + 1..=y_max).map(|y| universe.at(&Position { x: x_max, y }).unwrap());
+            let x_fields = (x_min + 1..=x_max).map(|x| &universe[Position { x, y: y_min }]);
+            let y_fields = (y_min + 1..=y_max).map(|y| &universe[Position { x: x_max, y }]);
             x_fields
                 .chain(y_fields)
                 .map(|e| e.actual_size())
@@ -1,6 +1,7 @@
 use core::panic;
 use itertools::iproduct;
 use itertools::Itertools;
+use std::collections::HashMap;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -89,7 +90,8 @@ impl Report {
         all_matching && !v_iter.any(|s| s == &SpringStatus::Damaged)
     }
 
-    fn iter_matches_bruteforce(&self) -> impl Iterator<Item = Vec<SpringStatus>> + '_ {
+    // tries every assignment of the unknown springs, only useful as a reference for `count_arrangements`
+    pub fn iter_matches_bruteforce(&self) -> impl Iterator<Item = Vec<SpringStatus>> + '_ {
         let unknown = self
             .visual
             .iter()
@@ -110,6 +112,53 @@ impl Report {
             })
             .filter(|visual| self.matches_numeric(visual))
     }
+
+    // the springs are repeated `copies` times with an unknown spring in between, the groups just `copies` times
+    pub fn unfold(&self, copies: usize) -> Report {
+        let visual = vec![self.visual.clone(); copies].join(&SpringStatus::Unknown);
+        Report {
+            visual,
+            numeric: self.numeric.repeat(copies),
+        }
+    }
+
+    pub fn count_arrangements(&self) -> usize {
+        self.count_from(0, 0, 0, &mut HashMap::new())
+    }
+
+    // memoised over the spring index, the group which is being matched and the length of the current damaged run
+    fn count_from(
+        &self,
+        index: usize,
+        group: usize,
+        run: usize,
+        memo: &mut HashMap<(usize, usize, usize), usize>,
+    ) -> usize {
+        let Some(spring) = self.visual.get(index) else {
+            let finished = match run {
+                0 => group == self.numeric.len(),
+                _ => group + 1 == self.numeric.len() && run == self.numeric[group],
+            };
+            return finished as usize;
+        };
+        if let Some(count) = memo.get(&(index, group, run)) {
+            return *count;
+        }
+
+        let damaged = || self.numeric.get(group).is_some_and(|n| run < *n);
+        let operational = || run == 0 || self.numeric.get(group) == Some(&run);
+
+        let mut count = 0;
+        if spring != &SpringStatus::Operational && damaged() {
+            count += self.count_from(index + 1, group, run + 1, memo);
+        }
+        if spring != &SpringStatus::Damaged && operational() {
+            let group = if run == 0 { group } else { group + 1 };
+            count += self.count_from(index + 1, group, 0, memo);
+        }
+        memo.insert((index, group, run), count);
+        count
+    }
 }
 
 #[derive(Debug)]
@@ -141,12 +190,18 @@ pub fn parse(input: &str) -> Vec<Report> {
         .collect()
 }
 
-#[aoc(day12, part1)]
-pub fn part1(input: &[Report]) -> usize {
+pub const UNFOLD_COPIES: usize = 5;
+
+pub fn total_arrangements(input: &[Report], copies: usize) -> usize {
     input
         .iter()
-        .map(|r| r.iter_matches_bruteforce().count())
+        .map(|r| r.unfold(copies).count_arrangements())
         .sum()
+}
+
+#[aoc(day12, part1)]
+pub fn part1(input: &[Report]) -> usize {
+    total_arrangements(input, 1)
 
     // input.iter().for_each(|r| {
     //     let solutions = r.iter_matches_bruteforce().collect_vec();
@@ -162,7 +217,7 @@ pub fn part1(input: &[Report]) -> usize {
 
 #[aoc(day12, part2)]
 pub fn part2(input: &[Report]) -> usize {
-    0
+    total_arrangements(input, UNFOLD_COPIES)
 }
 
 #[cfg(test)]
@@ -190,7 +245,36 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        assert_eq!(1, part2(&parse(TEST_INPUT)));
+        assert_eq!(525152, part2(&parse(TEST_INPUT)));
+    }
+
+    #[test]
+    fn test_count_arrangements_matches_bruteforce() {
+        for r in parse(TEST_INPUT) {
+            assert_eq!(r.iter_matches_bruteforce().count(), r.count_arrangements());
+
+            let unfolded = r.unfold(2);
+            assert_eq!(
+                unfolded.iter_matches_bruteforce().count(),
+                unfolded.count_arrangements()
+            );
+        }
+    }
+
+    #[test]
+    fn test_unfold() {
+        let r = Report::from_str(".# 1").unwrap();
+        let unfolded = r.unfold(3);
+
+        assert_eq!(visual_to_string(&unfolded.visual), ".#?.#?.#");
+        assert_eq!(unfolded.numeric, vec![1, 1, 1]);
+        assert_eq!(
+            parse(TEST_INPUT)
+                .iter()
+                .map(|r| r.unfold(UNFOLD_COPIES).count_arrangements())
+                .collect::<Vec<_>>(),
+            vec![1, 16384, 1, 16, 2500, 506250]
+        );
     }
 
     #[test]
@@ -1,10 +1,10 @@
-use std::{fmt::Debug, str::FromStr};
+use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::grid::Grid;
+use crate::{bitgrid::BitGrid, grid::Grid};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum GroundType {
     Ash,
     Rocks,
@@ -44,90 +44,89 @@ pub fn parse(input: &str) -> Vec<Grid<GroundType>> {
 }
 
 #[derive(Debug)]
-pub enum MirrorDirection {
-    Vertical,
-    Horizontal,
-}
-
-#[derive(Debug)]
-pub struct MirrorLineIter<'a, T> {
-    grid: &'a Grid<T>,
+pub struct MirrorLineIter {
+    rows: usize,
     mirror_line: usize,
     offset: usize,
-    dir: MirrorDirection,
 }
-impl<'a, T> MirrorLineIter<'a, T> {
-    fn new(grid: &'a Grid<T>, mirror_line: usize, dir: MirrorDirection) -> Self {
+impl MirrorLineIter {
+    fn new(rows: usize, mirror_line: usize) -> Self {
         Self {
-            grid,
+            rows,
             mirror_line,
             offset: 0,
-            dir,
         }
     }
 }
 
-impl<'a, T> Iterator for MirrorLineIter<'a, T> {
-    type Item = (Vec<&'a T>, Vec<&'a T>);
+// yields the indices of the row pairs mirrored around `mirror_line`
+impl Iterator for MirrorLineIter {
+    type Item = (usize, usize);
     fn next(&mut self) -> Option<Self::Item> {
         let lower_index = self.mirror_line.checked_sub(self.offset)?;
         let upper_index = self.mirror_line + 1 + self.offset;
         self.offset += 1;
 
-        match self.dir {
-            MirrorDirection::Horizontal => {
-                if upper_index >= self.grid.rows() {
-                    return None;
-                }
-                Some((
-                    self.grid.iter_row(lower_index).collect_vec(),
-                    self.grid.iter_row(upper_index).collect_vec(),
-                ))
-            }
-            MirrorDirection::Vertical => {
-                if upper_index >= self.grid.columns {
-                    return None;
-                }
-                Some((
-                    self.grid.iter_col(lower_index).collect_vec(),
-                    self.grid.iter_col(upper_index).collect_vec(),
-                ))
-            }
+        if upper_index >= self.rows {
+            return None;
         }
+        Some((lower_index, upper_index))
     }
 }
 
-fn check_mirror<T: PartialEq + Debug>(
-    grid: &Grid<T>,
-    mirror_line: usize,
-    dir: MirrorDirection,
-) -> bool {
-    let mut mirror_iter = MirrorLineIter::new(grid, mirror_line, dir);
-    mirror_iter.all(|(a, b)| dbg!(a == b))
-}
-
-fn calc_reflection_count<T: PartialEq + Debug>(grid: &Grid<T>) -> usize {
-    if let Some(horizontal_mirror) =
-        (0..grid.rows()).find(|i| check_mirror(grid, *i, MirrorDirection::Horizontal))
-    {
-        (horizontal_mirror + 1) * 100
-    } else if let Some(vertical_mirror) =
-        (0..grid.columns).find(|i| check_mirror(grid, *i, MirrorDirection::Vertical))
-    {
-        vertical_mirror + 1
-    } else {
-        0
+// a mirror line is between two rows or columns, the value is the count of rows above or columns left of it
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Reflection {
+    Horizontal(usize),
+    Vertical(usize),
+}
+
+impl Reflection {
+    fn summary(&self) -> usize {
+        match self {
+            Reflection::Horizontal(rows) => rows * 100,
+            Reflection::Vertical(columns) => *columns,
+        }
     }
 }
 
+// number of cells that differ from their reflection across the line below `mirror_line`
+fn mirror_differences(grid: &BitGrid, mirror_line: usize) -> usize {
+    MirrorLineIter::new(grid.rows(), mirror_line)
+        .map(|(a, b)| grid.row_hamming(a, b))
+        .sum()
+}
+
+fn find_horizontal_mirrors(grid: &BitGrid, smudges: usize) -> impl Iterator<Item = usize> + '_ {
+    (0..grid.rows().saturating_sub(1))
+        .filter(move |i| mirror_differences(grid, *i) == smudges)
+        .map(|i| i + 1)
+}
+
+// every line where exactly `smudges` cells have to change for a perfect reflection,
+// vertical mirrors are found as horizontal mirrors of the transposed grid
+pub fn find_reflections(grid: &Grid<GroundType>, smudges: usize) -> Vec<Reflection> {
+    let rocks = BitGrid::from_grid(grid, |t| t == &GroundType::Rocks);
+    let horizontal = find_horizontal_mirrors(&rocks, smudges).map(Reflection::Horizontal);
+    let transposed = rocks.transposed();
+    let vertical = find_horizontal_mirrors(&transposed, smudges).map(Reflection::Vertical);
+    horizontal.chain(vertical).collect()
+}
+
+fn calc_reflection_count(grid: &Grid<GroundType>, smudges: usize) -> usize {
+    find_reflections(grid, smudges)
+        .first()
+        .map_or(0, Reflection::summary)
+}
+
 #[aoc(day13, part1)]
 pub fn part1(input: &[Grid<GroundType>]) -> usize {
-    input.iter().map(calc_reflection_count).sum()
+    input.iter().map(|g| calc_reflection_count(g, 0)).sum()
 }
 
 #[aoc(day13, part2)]
 pub fn part2(input: &[Grid<GroundType>]) -> usize {
-    0
+    input.iter().map(|g| calc_reflection_count(g, 1)).sum()
 }
 
 #[cfg(test)]
@@ -163,6 +162,34 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        assert_eq!(1, part2(&parse(TEST_INPUT)));
+        assert_eq!(400, part2(&parse(TEST_INPUT)));
+    }
+
+    #[test]
+    fn test_find_reflections() {
+        let grids = parse(TEST_INPUT);
+        assert_eq!(
+            find_reflections(&grids[0], 0),
+            vec![Reflection::Vertical(5)]
+        );
+        assert_eq!(
+            find_reflections(&grids[1], 0),
+            vec![Reflection::Horizontal(4)]
+        );
+        assert_eq!(
+            find_reflections(&grids[0], 1),
+            vec![Reflection::Horizontal(3)]
+        );
+        assert_eq!(
+            find_reflections(&grids[1], 1),
+            vec![Reflection::Horizontal(1)]
+        );
+
+        let grid = Grid::from_str("##\n##\n").unwrap();
+        assert_eq!(
+            find_reflections(&grid, 0),
+            vec![Reflection::Horizontal(1), Reflection::Vertical(1)]
+        );
+        assert!(find_reflections(&grid, 1).is_empty());
     }
 }
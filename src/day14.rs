@@ -1,9 +1,8 @@
-use itertools::Itertools;
 use std::str::FromStr;
-use std::vec;
 
-use crate::day10::Direction;
-use crate::grid::Grid;
+use crate::bitgrid::BitGrid;
+use crate::cycle;
+use crate::grid::{ColumnMut, Direction, Grid};
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
 pub enum Element {
@@ -39,59 +38,61 @@ pub fn parse(input: &str) -> Grid<Element> {
     Grid::from_str(input).unwrap()
 }
 
-fn roll_rocks(elements: &mut [&mut Element]) {
-    let rolling_rocks = elements
-        .iter()
-        .positions(|e| e == &&Element::RoundedRock)
-        .collect_vec();
-
-    for rock in rolling_rocks {
-        if let Some(destination) = elements[..rock]
-            .iter_mut()
-            .rev()
-            .take_while(|e| e == &&&Element::EmptySpace)
-            .last()
-        {
-            **destination = Element::RoundedRock;
-            *elements[rock] = Element::EmptySpace;
+fn roll_rocks(column: &mut ColumnMut<Element>) {
+    let mut destination = 0;
+    for i in 0..column.len() {
+        match column[i] {
+            Element::CubeRock => destination = i + 1,
+            Element::RoundedRock => {
+                column.swap(destination, i);
+                destination += 1;
+            }
+            Element::EmptySpace => (),
         }
     }
 }
 
+fn roll_all_rocks_north(grid: &mut Grid<Element>) {
+    grid.iter_columns_mut()
+        .for_each(|mut column| roll_rocks(&mut column));
+}
+
 fn roll_all_rocks(grid: &mut Grid<Element>, dir: Direction) {
     match dir {
-        Direction::North => (0..grid.columns).for_each(|c| {
-            let mut col = grid.iter_col_mut(c).collect_vec();
-            roll_rocks(&mut col);
-        }),
-        Direction::South => (0..grid.columns).for_each(|c| {
-            let mut col = grid.iter_col_mut(c).collect_vec();
-            col.reverse();
-            roll_rocks(&mut col);
-        }),
-        Direction::West => (0..grid.rows()).for_each(|c| {
-            let mut row = grid.iter_row_mut(c).collect_vec();
-            roll_rocks(&mut row);
-        }),
-        Direction::East => (0..grid.rows()).for_each(|c| {
-            let mut row = grid.iter_row_mut(c).collect_vec();
-            row.reverse();
-            roll_rocks(&mut row);
-        }),
+        Direction::North => roll_all_rocks_north(grid),
+        Direction::South => {
+            grid.flip_vertical();
+            roll_all_rocks_north(grid);
+            grid.flip_vertical();
+        }
+        Direction::West => {
+            grid.rotate_cw();
+            roll_all_rocks_north(grid);
+            grid.rotate_ccw();
+        }
+        Direction::East => {
+            grid.rotate_ccw();
+            roll_all_rocks_north(grid);
+            grid.rotate_cw();
+        }
     }
 }
 
-fn cycle(grid: &mut Grid<Element>) {
+fn spin_cycle(grid: &mut Grid<Element>) {
     roll_all_rocks(grid, Direction::North);
     roll_all_rocks(grid, Direction::West);
     roll_all_rocks(grid, Direction::South);
     roll_all_rocks(grid, Direction::East);
 }
 
-fn calc_north_beam_load(grid: &Grid<Element>) -> usize {
-    grid.iter_rows()
-        .enumerate()
-        .map(|(i, r)| r.iter().filter(|e| e == &&&Element::RoundedRock).count() * (grid.rows() - i))
+// only the rounded rocks move, so together with the input they describe the whole platform
+fn rounded_rocks(grid: &Grid<Element>) -> BitGrid {
+    BitGrid::from_grid(grid, |e| e == &Element::RoundedRock)
+}
+
+fn calc_north_beam_load(rocks: &BitGrid) -> usize {
+    (0..rocks.rows())
+        .map(|i| rocks.row_count_ones(i) * (rocks.rows() - i))
         .sum()
 }
 
@@ -99,32 +100,25 @@ fn calc_north_beam_load(grid: &Grid<Element>) -> usize {
 pub fn part1(input: &Grid<Element>) -> usize {
     let mut grid = input.clone();
     roll_all_rocks(&mut grid, Direction::North);
-    calc_north_beam_load(&grid)
+    calc_north_beam_load(&rounded_rocks(&grid))
 }
 
 #[aoc(day14, part2)]
 pub fn part2(input: &Grid<Element>) -> usize {
-    let mut grid = input.clone();
-    let count: usize = 1000000000;
-
-    let mut grids: Vec<Grid<Element>> = vec![grid.clone()];
-    let mut grid_id = None;
-
-    for i in 1..=count {
-        cycle(&mut grid);
-        if let Some((j, _)) = grids.iter().enumerate().find(|(_, g)| g == &&grid) {
-            let first_occ = j;
-            let second_occ = i;
-            let repeat = second_occ - first_occ;
-
-            grid_id = Some(first_occ + (count - first_occ) % repeat);
-            break;
-        } else {
-            grids.push(grid.clone());
-        }
-    }
-
-    calc_north_beam_load(&grids[grid_id.unwrap()])
+    let cube_rocks = input.map(|e| match e {
+        Element::RoundedRock => Element::EmptySpace,
+        e => e.clone(),
+    });
+    let (cycle, states) = cycle::detect(rounded_rocks(input), |rocks| {
+        let mut grid = cube_rocks.clone();
+        rocks
+            .iter_ones()
+            .for_each(|p| grid[p] = Element::RoundedRock);
+        spin_cycle(&mut grid);
+        rounded_rocks(&grid)
+    });
+
+    calc_north_beam_load(&states[cycle.reduce(1000000000)])
 }
 
 #[cfg(test)]
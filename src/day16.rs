@@ -3,6 +3,7 @@ use itertools::Itertools;
 use rayon::prelude::*;
 use std::str::FromStr;
 
+use crate::bitgrid::BitGrid;
 use crate::grid::{Direction, Grid, Position};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -48,57 +49,20 @@ pub enum LightAction {
 
 impl Element {
     fn procss_light_beam(&self, moving_dir: Direction) -> LightAction {
-        use Direction::*;
         use LightAction::*;
 
         match self {
             Self::EmptySpace => Unchanged,
-            Self::MirrorForward => match moving_dir {
-                North => Redirect(East),
-                East => Redirect(North),
-                South => Redirect(West),
-                West => Redirect(South),
-            },
-            Self::MirrorBackward => match moving_dir {
-                North => Redirect(West),
-                East => Redirect(South),
-                South => Redirect(East),
-                West => Redirect(North),
-            },
-            Self::VerticalSplitter => match moving_dir {
-                North => Unchanged,
-                East => Split(North, South),
-                South => Unchanged,
-                West => Split(North, South),
-            },
-            Self::HorizontalSplitter => match moving_dir {
-                North => Split(West, East),
-                East => Unchanged,
-                South => Split(West, East),
-                West => Unchanged,
-            },
-        }
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Default, Clone)]
-pub struct EnergizedElement {
-    element: Element,
-    energy_count: usize,
-}
-impl From<char> for EnergizedElement {
-    fn from(value: char) -> Self {
-        Self {
-            element: Element::from(value),
-            energy_count: 0,
-        }
-    }
-}
-impl From<EnergizedElement> for char {
-    fn from(value: EnergizedElement) -> Self {
-        match value.element {
-            Element::EmptySpace if value.energy_count > 0 => '#',
-            _ => char::from(value.element),
+            // '/' turns vertical beams right and horizontal beams left, '\' the other way round
+            Self::MirrorForward if moving_dir.is_vertical() => Redirect(moving_dir.turn_right()),
+            Self::MirrorForward => Redirect(moving_dir.turn_left()),
+            Self::MirrorBackward if moving_dir.is_vertical() => Redirect(moving_dir.turn_left()),
+            Self::MirrorBackward => Redirect(moving_dir.turn_right()),
+            Self::VerticalSplitter if moving_dir.is_vertical() => Unchanged,
+            Self::HorizontalSplitter if !moving_dir.is_vertical() => Unchanged,
+            Self::VerticalSplitter | Self::HorizontalSplitter => {
+                Split(moving_dir.turn_left(), moving_dir.turn_right())
+            }
         }
     }
 }
@@ -119,8 +83,10 @@ impl LightBeam {
     }
 }
 
+// the energised cells are marked in a bit grid parallel to the contraption
 pub struct LightBeamIter<'a> {
-    grid: &'a mut Grid<EnergizedElement>,
+    grid: &'a Grid<Element>,
+    energy: &'a mut BitGrid,
     beams: Vec<LightBeam>,
 }
 
@@ -131,8 +97,8 @@ impl<'a> Iterator for LightBeamIter<'a> {
         let mut new_beams = vec![];
 
         self.beams.iter_mut().enumerate().for_each(|(i, b)| {
-            if let Some(element) = self.grid.at_mut(&b.position) {
-                match element.element.procss_light_beam(b.direction) {
+            if let Some(element) = self.grid.at(&b.position) {
+                match element.procss_light_beam(b.direction) {
                     LightAction::Unchanged => (),
                     LightAction::Redirect(dir) => b.direction = dir,
                     LightAction::Split(dir_a, dir_b) => {
@@ -143,7 +109,7 @@ impl<'a> Iterator for LightBeamIter<'a> {
                         });
                     }
                 }
-                element.energy_count += 1;
+                self.energy.set(&b.position, true);
             } else {
                 // remove out of bounds beams
                 beams_to_remove.push(i);
@@ -169,23 +135,25 @@ impl<'a> Iterator for LightBeamIter<'a> {
             None
         } else {
             // println!("{}", self.grid);
-            Some(get_energy_level(self.grid))
+            Some(get_energy_level(self.energy))
         }
     }
 }
 
 #[aoc_generator(day16)]
-pub fn parse(input: &str) -> Grid<EnergizedElement> {
+pub fn parse(input: &str) -> Grid<Element> {
     Grid::from_str(input).unwrap()
 }
 
-fn get_energy_level(grid: &Grid<EnergizedElement>) -> usize {
-    grid.iter().filter(|e| e.energy_count > 0).count()
+fn get_energy_level(energy: &BitGrid) -> usize {
+    energy.count_ones()
 }
 
-fn process_light_beams(grid: &mut Grid<EnergizedElement>, init_beam: LightBeam) {
+fn process_light_beams(grid: &Grid<Element>, init_beam: LightBeam) -> BitGrid {
+    let mut energy_grid = BitGrid::new(grid.columns, grid.rows());
     let beam_iter = LightBeamIter {
         grid,
+        energy: &mut energy_grid,
         beams: vec![init_beam],
     };
     let mut old_energy = 0;
@@ -194,29 +162,27 @@ fn process_light_beams(grid: &mut Grid<EnergizedElement>, init_beam: LightBeam)
         if old_energy == energy {
             steady_count += 1;
             if steady_count > 10 {
-                return;
+                break;
             }
         } else {
             steady_count = 0;
         }
         old_energy = energy;
     }
+    energy_grid
 }
 
 #[aoc(day16, part1)]
-pub fn part1(input: &Grid<EnergizedElement>) -> usize {
-    let mut grid = input.clone();
-
+pub fn part1(input: &Grid<Element>) -> usize {
     let init_beam = LightBeam {
         position: Position { x: 0, y: 0 },
         direction: Direction::East,
     };
-    process_light_beams(&mut grid, init_beam);
-    get_energy_level(&grid)
+    get_energy_level(&process_light_beams(input, init_beam))
 }
 
 #[aoc(day16, part2)]
-pub fn part2(input: &Grid<EnergizedElement>) -> usize {
+pub fn part2(input: &Grid<Element>) -> usize {
     let beams = (0..input.columns).map(|y| LightBeam {
         position: Position { x: 0, y },
         direction: Direction::East,
@@ -244,11 +210,7 @@ pub fn part2(input: &Grid<EnergizedElement>) -> usize {
         .collect_vec()
         .into_par_iter()
         .progress()
-        .map(|b| {
-            let mut g = input.clone();
-            process_light_beams(&mut g, b);
-            get_energy_level(&g)
-        })
+        .map(|b| get_energy_level(&process_light_beams(input, b)))
         .max()
         .unwrap()
 }
@@ -1,20 +1,19 @@
-use std::{cmp::Ordering, collections::VecDeque, ops, str::FromStr};
+use std::{
+    io::{self, Write},
+    str::FromStr,
+};
 
 use itertools::Itertools;
 
-use crate::grid::{Direction, Grid, Position};
+use crate::compress::Compression;
+use crate::grid::{Direction, Grid, IntPosition, Position, SparseGrid};
+use crate::render::Color;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DigEntry {
     direction: Direction,
     distance: usize,
-    color_str: String,
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
-pub struct IntPosition {
-    x: isize,
-    y: isize,
+    color: Color,
 }
 
 #[derive(Debug)]
@@ -29,31 +28,37 @@ impl FromStr for DigEntry {
 
         let direction = parts
             .next()
-            .map_or(Err(ParseError::InvalidDigEntry), |s| match s {
-                "R" => Ok(Direction::East),
-                "L" => Ok(Direction::West),
-                "U" => Ok(Direction::North),
-                "D" => Ok(Direction::South),
-                _ => Err(ParseError::InvalidDigEntry),
-            })?;
+            .ok_or(ParseError::InvalidDigEntry)?
+            .parse()
+            .map_err(|_| ParseError::InvalidDigEntry)?;
 
         let distance = parts.next().map_or(Err(ParseError::InvalidDigEntry), |s| {
             s.parse().map_err(|_| ParseError::InvalidDigEntry)
         })?;
 
-        let color_str = parts
+        let color = parts
             .next()
-            .map(|s| s.to_string())
+            .and_then(parse_color)
             .ok_or(ParseError::InvalidDigEntry)?;
 
         Ok(Self {
             direction,
             distance,
-            color_str,
+            color,
         })
     }
 }
 
+// the colour of a dig instruction, e.g. `(#70c710)`
+fn parse_color(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix("(#")?.strip_suffix(')')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
 #[aoc_generator(day18, part1)]
 pub fn parse1(input: &str) -> Vec<DigEntry> {
     input
@@ -67,30 +72,19 @@ pub fn parse2(input: &str) -> Vec<Position> {
     let instructions = input.lines().map(|l| {
         let (_, code) = l.split_once('#').unwrap();
         let distance = usize::from_str_radix(&code[..5], 16).unwrap();
-        let direction = match code.chars().nth(5).unwrap() {
-            '0' => Direction::East,
-            '1' => Direction::South,
-            '2' => Direction::West,
-            '3' => Direction::North,
-            _ => panic!(),
-        };
+        let direction = Direction::from_hex_digit(code.chars().nth(5).unwrap()).unwrap();
 
         DigEntry {
             direction,
             distance,
-            color_str: String::new(),
+            color: Color::White,
         }
     });
 
     let positions = instructions
         .scan(IntPosition { x: 0, y: 0 }, |state, step| {
-            match step.direction {
-                Direction::North => state.y -= step.distance as isize,
-                Direction::East => state.x += step.distance as isize,
-                Direction::South => state.y += step.distance as isize,
-                Direction::West => state.x -= step.distance as isize,
-            };
-            Some(state.clone())
+            *state = state.move_by(&step.direction, step.distance);
+            Some(*state)
         })
         .collect_vec();
 
@@ -111,7 +105,7 @@ pub fn parse2(input: &str) -> Vec<Position> {
 pub enum Terrain {
     #[default]
     GroundLevel,
-    Trench(Option<String>),
+    Trench(Option<Color>),
 }
 impl From<Terrain> for char {
     fn from(value: Terrain) -> Self {
@@ -122,103 +116,73 @@ impl From<Terrain> for char {
     }
 }
 
-fn expand_grid<T: Default + Clone>(
-    grid: &mut Grid<T>,
-    position: &mut Position,
-    direction: &Direction,
-    distance: usize,
-) {
-    match direction {
-        Direction::North => {
-            let existing_space = position.y;
-            if distance.cmp(&existing_space) == std::cmp::Ordering::Greater {
-                let expansion = distance - existing_space;
-                (0..expansion).for_each(|_| grid.insert_row_default(0));
-                position.y += expansion;
-            }
-        }
-        Direction::East => {
-            let existing_space = grid.columns - position.x - 1;
-            if distance.cmp(&existing_space) == std::cmp::Ordering::Greater {
-                let expansion = distance - existing_space;
-                (0..expansion).for_each(|_| grid.insert_col_default(grid.columns));
-            }
-        }
-        Direction::South => {
-            let existing_space = grid.rows() - position.y - 1;
-            if distance.cmp(&existing_space) == std::cmp::Ordering::Greater {
-                let expansion = distance - existing_space;
-                (0..expansion).for_each(|_| grid.insert_row_default(grid.rows()));
-            }
-        }
-        Direction::West => {
-            let existing_space = position.x;
-            if distance.cmp(&existing_space) == std::cmp::Ordering::Greater {
-                let expansion = distance - existing_space;
-                (0..expansion).for_each(|_| grid.insert_col_default(0));
-                position.x += expansion;
-            }
-        }
+// trench cells are painted with the colour of the dig instruction
+fn terrain_color(terrain: &Terrain) -> Color {
+    match terrain {
+        Terrain::GroundLevel => Color::Black,
+        Terrain::Trench(None) => Color::White,
+        Terrain::Trench(Some(color)) => *color,
     }
 }
 
 fn dig_trench(instructions: &[DigEntry]) -> Grid<Terrain> {
-    let mut grid: Grid<Terrain> = Grid::new(vec![Terrain::Trench(None)], 1);
-    let mut current_pos = Position { x: 0, y: 0 };
+    let mut grid = SparseGrid::new(Terrain::GroundLevel);
+    let mut current_pos = IntPosition { x: 0, y: 0 };
+    grid.insert(current_pos, Terrain::Trench(None));
 
     for step in instructions {
-        expand_grid(&mut grid, &mut current_pos, &step.direction, step.distance);
-        (0..step.distance).for_each(|_| {
-            current_pos = current_pos.move_dir(&step.direction).unwrap();
-            let e = grid.at_mut(&current_pos).unwrap();
-            *e = Terrain::Trench(Some(step.color_str.clone()));
+        (1..=step.distance).for_each(|n| {
+            let pos = current_pos.move_by(&step.direction, n);
+            grid.insert(pos, Terrain::Trench(Some(step.color)));
         });
+        current_pos = current_pos.move_by(&step.direction, step.distance);
     }
 
-    grid
+    grid.to_grid()
 }
 
-fn flood_filll(grid: &mut Grid<Terrain>, position: &Position) {
-    let mut queue = VecDeque::new();
-    queue.push_back(*position);
+pub fn write_trench_ppm<W: Write>(
+    instructions: &[DigEntry],
+    writer: W,
+    scale: usize,
+) -> io::Result<()> {
+    dig_trench(instructions).write_ppm(writer, scale, terrain_color)
+}
 
-    while let Some(current_pos) = queue.pop_front() {
-        let current = grid.at_mut(&current_pos).unwrap();
-        if matches!(current, Terrain::Trench(_)) {
-            continue;
-        }
+// every ground region which does not reach the edge of the map is enclosed by the trench
+fn fill_trench(grid: &mut Grid<Terrain>) {
+    let (labels, regions) = grid
+        .label_regions(|a, b| matches!(a, Terrain::Trench(_)) == matches!(b, Terrain::Trench(_)));
 
-        *current = Terrain::Trench(None);
-        if let Some(pos) = current_pos.move_dir(&Direction::North) {
-            queue.push_back(pos);
-        }
-        if let Some(pos) = current_pos.move_dir(&Direction::East) {
-            queue.push_back(pos);
-        }
-        if let Some(pos) = current_pos.move_dir(&Direction::West) {
-            queue.push_back(pos);
-        }
-        if let Some(pos) = current_pos.move_dir(&Direction::South) {
-            queue.push_back(pos);
-        }
-    }
+    grid.iter_mut()
+        .zip(labels.iter())
+        .filter(|(t, id)| t == &&Terrain::GroundLevel && !regions[id.0].touches_border)
+        .for_each(|(t, _)| *t = Terrain::Trench(None));
 }
 
-fn fill_trench(grid: &mut Grid<Terrain>) {
-    let (i, _) = grid
-        .iter_row(0)
-        .enumerate()
-        .find(|(i, e)| {
-            matches!(e, Terrain::Trench(_))
-                && matches!(
-                    grid.at(&Position { x: *i, y: 1 }).unwrap(),
-                    Terrain::GroundLevel
-                )
-        })
-        .unwrap();
+// the same fill as part 1 on a compressed grid, so it works for the huge part 2 distances as well
+pub fn compressed_area(vertices: &[Position]) -> usize {
+    let vertices = vertices.iter().map(|p| IntPosition::from(*p)).collect_vec();
+    let compression = Compression::new(vertices.iter().copied());
+    let mut grid = compression.grid(Terrain::GroundLevel);
+
+    for (a, b) in vertices.iter().circular_tuple_windows() {
+        let a = compression.compress(a).unwrap();
+        let b = compression.compress(b).unwrap();
+        for y in a.y.min(b.y)..=a.y.max(b.y) {
+            for x in a.x.min(b.x)..=a.x.max(b.x) {
+                grid[Position { x, y }] = Terrain::Trench(None);
+            }
+        }
+    }
+    fill_trench(&mut grid);
 
-    let start_pos = Position { x: i, y: 1 };
-    flood_filll(grid, &start_pos);
+    grid.zip_with(&compression.weights(), |t, weight| match t {
+        Terrain::Trench(_) => *weight,
+        Terrain::GroundLevel => 0,
+    })
+    .iter()
+    .sum()
 }
 
 #[aoc(day18, part1)]
@@ -283,6 +247,21 @@ U 2 (#7a21e3)";
         assert_eq!(expected, parse1(TEST_INPUT));
     }
 
+    #[test]
+    fn test_parse_color() {
+        let entry = DigEntry::from_str("R 6 (#70c710)").unwrap();
+        assert_eq!(entry.color, Color::Rgb(0x70, 0xc7, 0x10));
+
+        for line in [
+            "R 6 (#70c71)",
+            "R 6 (#70c7100)",
+            "R 6 (#+0c710)",
+            "R 6 70c710",
+        ] {
+            assert!(DigEntry::from_str(line).is_err());
+        }
+    }
+
     #[test]
     fn test_parse2() {
         let positions = parse2(TEST_INPUT);
@@ -302,6 +281,18 @@ U 2 (#7a21e3)";
         assert_eq!(62, part1(&parse1(TEST_INPUT)));
     }
 
+    #[test]
+    fn test_trench_image() {
+        let mut ppm = vec![];
+        write_trench_ppm(&parse1(TEST_INPUT), &mut ppm, 2).unwrap();
+
+        let header = b"P6\n14 20\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 14 * 20 * 3);
+        // the origin is dug last by `U 2 (#7a21e3)`
+        assert_eq!(&ppm[header.len()..header.len() + 3], &[0x7a, 0x21, 0xe3]);
+    }
+
     #[test]
     fn solve_part1() {
         assert_eq!(
@@ -349,6 +340,26 @@ U 2 (#7a21e3)";
         assert_eq!(952408144115, part2(&parse2(TEST_INPUT)));
     }
 
+    #[test]
+    fn test_compressed_area() {
+        assert_eq!(952408144115, compressed_area(&parse2(TEST_INPUT)));
+
+        let positions = [
+            Position { x: 0, y: 0 },
+            Position { x: 1, y: 0 },
+            Position { x: 1, y: 2 },
+            Position { x: 3, y: 2 },
+            Position { x: 3, y: 0 },
+            Position { x: 4, y: 0 },
+            Position { x: 4, y: 3 },
+            Position { x: 0, y: 3 },
+        ];
+        assert_eq!(part2(&positions), compressed_area(&positions));
+
+        let positions = parse2(include_str!("../input/2023/day18.txt"));
+        assert_eq!(part2(&positions), compressed_area(&positions));
+    }
+
     #[test]
     fn solve_part2() {
         assert_eq!(1, part2(&parse2(include_str!("../input/2023/day18.txt"))));
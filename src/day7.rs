@@ -1,8 +1,4 @@
-use std::{
-    cmp::Ordering,
-    collections::{BTreeMap, BinaryHeap},
-    str::FromStr,
-};
+use std::{cmp::Ordering, str::FromStr};
 
 #[derive(Debug)]
 pub enum ParseError {
@@ -11,7 +7,7 @@ pub enum ParseError {
     InvalidHandWithBet,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum Card {
     Ass = 14,
     King = 13,
@@ -50,7 +46,7 @@ impl TryFrom<char> for Card {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum Type {
     HighCard = 0,
     OnePair,
@@ -61,6 +57,29 @@ pub enum Type {
     FiveOfAKind,
 }
 
+// how cards and hand types are ranked, with jokers `J` is the weakest card but stands in for any other card
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Rules {
+    pub jokers: bool,
+}
+
+impl Rules {
+    pub const STANDARD: Rules = Rules { jokers: false };
+    pub const JOKERS: Rules = Rules { jokers: true };
+
+    fn is_joker(&self, card: &Card) -> bool {
+        self.jokers && card == &Card::Jack
+    }
+
+    fn card_value(&self, card: &Card) -> u8 {
+        if self.is_joker(card) {
+            1
+        } else {
+            *card as u8
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Hand([Card; 5]);
 
@@ -78,46 +97,44 @@ impl FromStr for Hand {
 }
 
 impl Hand {
-    fn get_type(&self) -> Type {
-        let map = self.0.iter().fold(BTreeMap::new(), |mut map, card| {
-            *map.entry(card).or_insert(0usize) += 1;
-            map
-        });
-
-        let mut sorted_values: BinaryHeap<_> = map.values().collect();
-
-        match sorted_values.pop().unwrap() {
-            5 => Type::FiveOfAKind,
-            4 => Type::FourOfAKind,
-            3 => match sorted_values.pop().unwrap() {
-                2 => Type::FullHouse,
-                _ => Type::ThreeOfAKind,
-            },
-            2 => match sorted_values.pop().unwrap() {
-                2 => Type::TwoPair,
-                _ => Type::OnePair,
-            },
+    fn get_type(&self, rules: &Rules) -> Type {
+        let mut counts = [0usize; 15];
+        self.0
+            .iter()
+            .filter(|card| !rules.is_joker(card))
+            .for_each(|card| counts[*card as usize] += 1);
+        let jokers = self.0.len() - counts.iter().sum::<usize>();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        // jokers always join the largest group
+        match (counts[0] + jokers, counts[1]) {
+            (5, _) => Type::FiveOfAKind,
+            (4, _) => Type::FourOfAKind,
+            (3, 2) => Type::FullHouse,
+            (3, _) => Type::ThreeOfAKind,
+            (2, 2) => Type::TwoPair,
+            (2, _) => Type::OnePair,
             _ => Type::HighCard,
         }
     }
+
+    // the type in the highest bits followed by four bits per card, so comparing strengths ranks the hands
+    pub fn strength(&self, rules: &Rules) -> u32 {
+        self.0
+            .iter()
+            .fold(self.get_type(rules) as u32, |strength, card| {
+                (strength << 4) | rules.card_value(card) as u32
+            })
+    }
+
+    pub fn cmp_with(&self, other: &Self, rules: &Rules) -> Ordering {
+        self.strength(rules).cmp(&other.strength(rules))
+    }
 }
 
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let self_type = self.get_type();
-        let other_type = other.get_type();
-
-        match &self_type.cmp(&other_type) {
-            Ordering::Equal => {
-                let zipped = self.0.iter().zip(other.0.iter());
-                // get the tie breaker
-                zipped
-                    .map(|(a, b)| a.cmp(b))
-                    .find(|cmp| cmp != &Ordering::Equal)
-                    .unwrap_or(Ordering::Equal)
-            }
-            other => *other,
-        }
+        self.cmp_with(other, &Rules::STANDARD)
     }
 }
 
@@ -165,33 +182,29 @@ pub fn parse(input: &str) -> Vec<HandWithBid> {
         .unwrap()
 }
 
-#[aoc(day7, part1)]
-pub fn part1(input: &[HandWithBid]) -> usize {
-    let len = input.len();
+// every strength is computed once and the hands are sorted by it
+fn total_winnings(input: &[HandWithBid], rules: &Rules) -> usize {
+    let mut hands = input
+        .iter()
+        .map(|h| (h.hand.strength(rules), h.bid))
+        .collect::<Vec<_>>();
+    hands.sort_unstable_by_key(|(strength, _)| *strength);
 
-    let mut heap = input.iter().collect::<BinaryHeap<_>>();
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, (_, bid))| bid * (i + 1))
+        .sum()
+}
 
-    // TODO: could be an iterator with nightly
-    let mut sum = 0;
-    let mut count = 0;
-    while let Some(h) = heap.pop() {
-        let rank = len - count;
-        count += 1;
-        // println!(
-        //     "{}: {:?} -> {:?} bid: {}",
-        //     rank,
-        //     h.hand.get_type(),
-        //     h.hand,
-        //     h.bid
-        // );
-        sum += h.bid * rank;
-    }
-    sum
+#[aoc(day7, part1)]
+pub fn part1(input: &[HandWithBid]) -> usize {
+    total_winnings(input, &Rules::STANDARD)
 }
 
 #[aoc(day7, part2)]
 pub fn part2(input: &[HandWithBid]) -> usize {
-    0
+    total_winnings(input, &Rules::JOKERS)
 }
 
 #[cfg(test)]
@@ -240,4 +253,43 @@ QQQJA 483";
     fn test_part2() {
         assert_eq!(5905, part2(&parse(TEST_INPUT)));
     }
+
+    #[test]
+    fn test_strength() {
+        let hand = |s| Hand::from_str(s).unwrap();
+
+        assert_eq!(hand("32T3K").strength(&Rules::STANDARD), 0x1_32a3d);
+        assert_eq!(hand("KTJJT").strength(&Rules::STANDARD), 0x2_dabba);
+        assert_eq!(hand("KTJJT").strength(&Rules::JOKERS), 0x5_da11a);
+        assert!(hand("AAAAK").strength(&Rules::STANDARD) < hand("2222J").strength(&Rules::JOKERS));
+    }
+
+    #[test]
+    fn test_joker_rules() {
+        let hand = |s| Hand::from_str(s).unwrap();
+
+        assert_eq!(hand("QJJQ2").get_type(&Rules::STANDARD), Type::TwoPair);
+        assert_eq!(hand("QJJQ2").get_type(&Rules::JOKERS), Type::FourOfAKind);
+        assert_eq!(hand("JJJJJ").get_type(&Rules::JOKERS), Type::FiveOfAKind);
+        assert_eq!(hand("T55J5").get_type(&Rules::JOKERS), Type::FourOfAKind);
+        assert_eq!(hand("2345J").get_type(&Rules::JOKERS), Type::OnePair);
+
+        // J is the weakest card in tie breaks
+        assert_eq!(
+            hand("JKKK2").cmp_with(&hand("QQQQ2"), &Rules::JOKERS),
+            Ordering::Less
+        );
+        assert_eq!(
+            hand("JKKK2").cmp_with(&hand("QQQQ2"), &Rules::STANDARD),
+            Ordering::Less
+        );
+        assert_eq!(
+            hand("J2345").cmp_with(&hand("23456"), &Rules::JOKERS),
+            Ordering::Greater
+        );
+        assert_eq!(
+            hand("JJ234").cmp_with(&hand("22345"), &Rules::JOKERS),
+            Ordering::Greater
+        );
+    }
 }
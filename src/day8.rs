@@ -1,5 +1,7 @@
 use std::{collections::BTreeMap, str::FromStr};
 
+use crate::cycle;
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct NodeMap {
     //TODO: these members need to be owned instead of &str because of an aoc framework limitation
@@ -12,6 +14,15 @@ impl NodeMap {
         let start_node = self.node_map.first_key_value().unwrap().0;
         self.iter_nodes_from(start_node)
     }
+    fn next_node(&self, node: &str, lr_index: usize) -> &str {
+        let choices = &self.node_map[node];
+        match self.lr.as_bytes()[lr_index] {
+            b'L' => &choices.0,
+            b'R' => &choices.1,
+            _ => panic!(),
+        }
+    }
+
     fn iter_nodes_from<'a>(&'a self, node: &'a str) -> NodeMapIter<'a> {
         let lr_iter = Box::new(self.lr.chars().cycle());
         NodeMapIter {
@@ -88,27 +99,20 @@ pub fn part1(input: &NodeMap) -> usize {
 
 #[aoc(day8, part2)]
 pub fn part2(input: &NodeMap) -> usize {
-    let start_node_iters = input
+    // the walk from every start node ends up in a loop through its end node, the state also has to
+    // track the position in the instructions
+    input
         .node_map
         .keys()
         .filter(|k| k.ends_with('A'))
-        .map(|n| input.iter_nodes_from(n));
-
-    start_node_iters
-        .map(|mut i| {
-            let (_, end_node) = i
-                .by_ref()
-                .enumerate()
-                .find(|(i, k)| k.ends_with('Z'))
-                .unwrap();
-            let period = i
-                .by_ref()
-                .enumerate()
-                .find(|(i, k)| *k == end_node)
-                .unwrap()
-                .0
-                + 1;
-            period
+        .map(|n| {
+            cycle::brent((n.as_str(), 0), |&(node, lr_index)| {
+                (
+                    input.next_node(node, lr_index),
+                    (lr_index + 1) % input.lr.len(),
+                )
+            })
+            .period
         })
         .reduce(num::integer::lcm)
         .unwrap()
@@ -159,4 +163,12 @@ XXX = (XXX, XXX)";
     fn test_part2() {
         assert_eq!(6, part2(&parse(TEST_INPUT2)));
     }
+
+    #[test]
+    fn solve_part2() {
+        assert_eq!(
+            21003205388413,
+            part2(&parse(include_str!("../input/2023/day8.txt")))
+        );
+    }
 }
@@ -1,14 +1,26 @@
 use core::fmt;
+use std::collections::{HashMap, VecDeque};
+use std::iter::StepBy;
+use std::marker::PhantomData;
+use std::ops::{Add, Index, IndexMut, Sub};
+use std::ptr::NonNull;
+use std::slice;
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-pub enum Direction {
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum Direction4 {
     North = 0,
     West,
     South,
     East,
 }
-impl Direction {
+pub use Direction4 as Direction;
+
+impl Direction4 {
+    pub fn all() -> [Direction4; 4] {
+        DIRECTIONS4
+    }
+
     pub fn invert(&self) -> Direction {
         match self {
             Direction::North => Direction::South,
@@ -17,9 +29,172 @@ impl Direction {
             Direction::West => Direction::East,
         }
     }
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::North | Direction::South)
+    }
+
+    pub fn delta(&self) -> (isize, isize) {
+        Direction8::from(*self).delta()
+    }
+
+    // digit encoding of the 2023 day18 hex instructions
+    pub fn from_hex_digit(c: char) -> Result<Direction, InvalidDirection> {
+        match c {
+            '0' => Ok(Direction::East),
+            '1' => Ok(Direction::South),
+            '2' => Ok(Direction::West),
+            '3' => Ok(Direction::North),
+            _ => Err(InvalidDirection(c.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InvalidDirection(pub String);
+
+impl fmt::Display for InvalidDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a direction", self.0)
+    }
+}
+
+impl std::error::Error for InvalidDirection {}
+
+// accepts U/D/L/R, N/E/S/W and ^v<>
+impl TryFrom<char> for Direction4 {
+    type Error = InvalidDirection;
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' | 'N' | '^' => Ok(Direction::North),
+            'R' | 'E' | '>' => Ok(Direction::East),
+            'D' | 'S' | 'v' => Ok(Direction::South),
+            'L' | 'W' | '<' => Ok(Direction::West),
+            _ => Err(InvalidDirection(value.to_string())),
+        }
+    }
+}
+
+impl FromStr for Direction4 {
+    type Err = InvalidDirection;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c).map_err(|_| InvalidDirection(s.to_string())),
+            _ => Err(InvalidDirection(s.to_string())),
+        }
+    }
+}
+
+const DIRECTIONS4: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+// discriminants go clockwise starting north
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum Direction8 {
+    North = 0,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+impl Direction8 {
+    pub fn all() -> [Direction8; 8] {
+        DIRECTIONS8
+    }
+
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn invert(&self) -> Direction8 {
+        DIRECTIONS8[(*self as usize + 4) % 8]
+    }
+
+    // turns by 45 degrees
+    pub fn turn_left(&self) -> Direction8 {
+        DIRECTIONS8[(*self as usize + 7) % 8]
+    }
+
+    pub fn turn_right(&self) -> Direction8 {
+        DIRECTIONS8[(*self as usize + 1) % 8]
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        *self as usize % 2 == 1
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+        }
+    }
+}
+
+impl TryFrom<Direction8> for Direction4 {
+    type Error = Direction8;
+    fn try_from(value: Direction8) -> Result<Self, Self::Error> {
+        match value {
+            Direction8::North => Ok(Direction::North),
+            Direction8::East => Ok(Direction::East),
+            Direction8::South => Ok(Direction::South),
+            Direction8::West => Ok(Direction::West),
+            _ => Err(value),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+const DIRECTIONS8: [Direction8; 8] = [
+    Direction8::North,
+    Direction8::NorthEast,
+    Direction8::East,
+    Direction8::SouthEast,
+    Direction8::South,
+    Direction8::SouthWest,
+    Direction8::West,
+    Direction8::NorthWest,
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub struct Position {
     pub x: usize,
     pub y: usize,
@@ -44,6 +219,99 @@ impl Position {
             Direction::West => self.x.checked_sub(1).map(|x| Position { x, y: self.y }),
         }
     }
+
+    // `None` when the move would leave the non-negative quadrant
+    pub fn move_by(&self, dir: &Direction, n: usize) -> Option<Position> {
+        let (dx, dy) = dir.delta();
+        self.checked_add_signed((dx * n as isize, dy * n as isize))
+    }
+
+    pub fn checked_add_signed(&self, (dx, dy): (isize, isize)) -> Option<Position> {
+        Some(Position {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+}
+
+// like plain usize arithmetic these panic when a coordinate would become negative
+impl Add<(isize, isize)> for Position {
+    type Output = Position;
+    fn add(self, rhs: (isize, isize)) -> Self::Output {
+        self.checked_add_signed(rhs)
+            .expect("Position would become negative")
+    }
+}
+
+impl Sub<(isize, isize)> for Position {
+    type Output = Position;
+    fn sub(self, (dx, dy): (isize, isize)) -> Self::Output {
+        self + (-dx, -dy)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash, PartialOrd, Ord)]
+pub struct IntPosition {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl IntPosition {
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn move_dir(&self, dir: &Direction) -> IntPosition {
+        match dir {
+            Direction::North => IntPosition {
+                x: self.x,
+                y: self.y - 1,
+            },
+            Direction::East => IntPosition {
+                x: self.x + 1,
+                y: self.y,
+            },
+            Direction::South => IntPosition {
+                x: self.x,
+                y: self.y + 1,
+            },
+            Direction::West => IntPosition {
+                x: self.x - 1,
+                y: self.y,
+            },
+        }
+    }
+
+    pub fn move_by(&self, dir: &Direction, n: usize) -> IntPosition {
+        let (dx, dy) = dir.delta();
+        *self + (dx * n as isize, dy * n as isize)
+    }
+}
+
+impl Add<(isize, isize)> for IntPosition {
+    type Output = IntPosition;
+    fn add(self, (dx, dy): (isize, isize)) -> Self::Output {
+        IntPosition {
+            x: self.x + dx,
+            y: self.y + dy,
+        }
+    }
+}
+
+impl Sub<(isize, isize)> for IntPosition {
+    type Output = IntPosition;
+    fn sub(self, (dx, dy): (isize, isize)) -> Self::Output {
+        self + (-dx, -dy)
+    }
+}
+
+impl From<Position> for IntPosition {
+    fn from(value: Position) -> Self {
+        IntPosition {
+            x: value.x as isize,
+            y: value.y as isize,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default, Hash)]
@@ -56,13 +324,65 @@ impl<T> Grid<T> {
         Grid { data, columns }
     }
 
+    pub fn from_fn<F: FnMut(Position) -> T>(columns: usize, rows: usize, mut f: F) -> Self {
+        let data = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| Position { x, y }))
+            .map(&mut f)
+            .collect();
+        Grid { data, columns }
+    }
+
+    // the column count is taken from the first row, every other row has to match it
+    pub fn from_rows<I, R>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = T>,
+    {
+        let mut grid = Grid::new(vec![], 0);
+        for (i, row) in rows.into_iter().enumerate() {
+            let before = grid.data.len();
+            grid.data.extend(row);
+            let length = grid.data.len() - before;
+            if i == 0 {
+                grid.columns = length;
+            } else if length != grid.columns {
+                panic!("Inconsistent row length");
+            }
+        }
+        grid
+    }
+
     pub fn is_position_valid(&self, pos: &Position) -> bool {
         if pos.x >= self.columns {
             return false;
         }
         let offset = self.columns * pos.y + pos.x;
 
-        offset <= self.data.len()
+        offset < self.data.len()
+    }
+
+    // the neighbouring position in `dir`, as long as it is still inside the grid
+    pub fn step(&self, pos: &Position, dir: &Direction) -> Option<Position> {
+        pos.move_dir(dir).filter(|p| self.is_position_valid(p))
+    }
+
+    // every cell from the one next to `pos` up to the edge of the grid, `pos` itself is not part of the ray
+    pub fn ray(&self, pos: &Position, dir: Direction) -> impl Iterator<Item = (Position, &T)> {
+        std::iter::successors(self.step(pos, &dir), move |p| self.step(p, &dir))
+            .map(|p| (p, &self.data[self.columns * p.y + p.x]))
+    }
+
+    // first cell on the ray matching `hit` and the number of steps taken to get there
+    pub fn cast_until<P: FnMut(&T) -> bool>(
+        &self,
+        pos: &Position,
+        dir: Direction,
+        mut hit: P,
+    ) -> Option<(Position, usize)> {
+        self.ray(pos, dir)
+            .enumerate()
+            .find(|(_, (_, t))| hit(t))
+            .map(|(i, (p, _))| (p, i + 1))
     }
 
     pub fn at(&self, pos: &Position) -> Option<&T> {
@@ -88,6 +408,116 @@ impl<T> Grid<T> {
         }
     }
 
+    fn position_to_index(&self, pos: &Position) -> Option<usize> {
+        if pos.x >= self.columns {
+            return None;
+        }
+        let offset = self.columns * pos.y + pos.x;
+        (offset < self.data.len()).then_some(offset)
+    }
+
+    pub fn neighbors4(&self, pos: &Position) -> impl Iterator<Item = (Direction, Position, &T)> {
+        let pos = *pos;
+        DIRECTIONS4.into_iter().filter_map(move |d| {
+            let p = pos.move_dir(&d)?;
+            self.at(&p).map(|t| (d, p, t))
+        })
+    }
+
+    pub fn neighbors8(&self, pos: &Position) -> impl Iterator<Item = (Direction8, Position, &T)> {
+        let pos = *pos;
+        DIRECTIONS8.into_iter().filter_map(move |d| {
+            let p = pos.checked_add_signed(d.delta())?;
+            self.at(&p).map(|t| (d, p, t))
+        })
+    }
+
+    pub fn neighbors4_mut(
+        &mut self,
+        pos: &Position,
+    ) -> impl Iterator<Item = (Direction, Position, &mut T)> {
+        let targets = DIRECTIONS4
+            .into_iter()
+            .filter_map(|d| {
+                let p = pos.move_dir(&d)?;
+                self.position_to_index(&p).map(|i| (d, p, i))
+            })
+            .collect::<Vec<_>>();
+        self.disjoint_mut(targets)
+    }
+
+    pub fn neighbors8_mut(
+        &mut self,
+        pos: &Position,
+    ) -> impl Iterator<Item = (Direction8, Position, &mut T)> {
+        let targets = DIRECTIONS8
+            .into_iter()
+            .filter_map(|d| {
+                let p = pos.checked_add_signed(d.delta())?;
+                self.position_to_index(&p).map(|i| (d, p, i))
+            })
+            .collect::<Vec<_>>();
+        self.disjoint_mut(targets)
+    }
+
+    // treats the grid as one tile of an infinite repetition, the second value is the tile the position lies in
+    pub fn wrap_position(&self, pos: &IntPosition) -> (Position, IntPosition) {
+        let (columns, rows) = (self.columns as isize, self.rows() as isize);
+        let wrapped = Position {
+            x: pos.x.rem_euclid(columns) as usize,
+            y: pos.y.rem_euclid(rows) as usize,
+        };
+        let tile = IntPosition {
+            x: pos.x.div_euclid(columns),
+            y: pos.y.div_euclid(rows),
+        };
+        (wrapped, tile)
+    }
+
+    pub fn at_wrapped(&self, pos: &IntPosition) -> (&T, IntPosition) {
+        let (wrapped, tile) = self.wrap_position(pos);
+        (&self.data[self.columns * wrapped.y + wrapped.x], tile)
+    }
+
+    pub fn at_wrapped_mut(&mut self, pos: &IntPosition) -> (&mut T, IntPosition) {
+        let (wrapped, tile) = self.wrap_position(pos);
+        (&mut self.data[self.columns * wrapped.y + wrapped.x], tile)
+    }
+
+    pub fn neighbors4_wrapped(
+        &self,
+        pos: &IntPosition,
+    ) -> impl Iterator<Item = (Direction, IntPosition, &T)> {
+        let pos = *pos;
+        DIRECTIONS4.into_iter().map(move |d| {
+            let p = pos.move_dir(&d);
+            (d, p, self.at_wrapped(&p).0)
+        })
+    }
+
+    // hands out mutable references to distinct cells, keeping the order of `targets`
+    fn disjoint_mut<D>(
+        &mut self,
+        targets: Vec<(D, Position, usize)>,
+    ) -> impl Iterator<Item = (D, Position, &mut T)> {
+        let mut targets = targets.into_iter().enumerate().collect::<Vec<_>>();
+        targets.sort_by_key(|(_, (_, _, i))| *i);
+
+        let mut rest = self.data.as_mut_slice();
+        let mut consumed = 0;
+        let mut refs = targets
+            .into_iter()
+            .map(|(order, (d, p, i))| {
+                let (head, tail) = std::mem::take(&mut rest).split_at_mut(i - consumed + 1);
+                rest = tail;
+                consumed = i + 1;
+                (order, (d, p, head.last_mut().unwrap()))
+            })
+            .collect::<Vec<_>>();
+        refs.sort_by_key(|(order, _)| *order);
+        refs.into_iter().map(|(_, r)| r)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.data.iter()
     }
@@ -127,7 +557,7 @@ impl<T> Grid<T> {
     }
 
     pub fn rows(&self) -> usize {
-        self.data.len() / self.columns
+        self.data.len().checked_div(self.columns).unwrap_or(0)
     }
 
     pub fn iter_row(&self, row_index: usize) -> impl Iterator<Item = &T> {
@@ -163,167 +593,1032 @@ impl<T> Grid<T> {
     }
 
     pub fn insert_row(&mut self, row_index: usize, data: Vec<T>) {
-        if row_index > self.rows() {
+        self.insert_rows([(row_index, data)]);
+    }
+
+    pub fn insert_col(&mut self, col_index: usize, data: Vec<T>) {
+        self.insert_cols([(col_index, data)]);
+    }
+
+    // row indices refer to the grid before the insertion, rows with the same index keep their order
+    pub fn insert_rows<I: IntoIterator<Item = (usize, Vec<T>)>>(&mut self, rows: I) {
+        let mut rows = rows.into_iter().collect::<Vec<_>>();
+        rows.sort_by_key(|(i, _)| *i);
+
+        let old_rows = self.rows();
+        if rows.iter().any(|(i, _)| *i > old_rows) {
             panic!("Out of bound row index");
         }
-        if data.len() != self.columns {
+        if rows.iter().any(|(_, d)| d.len() != self.columns) {
             panic!("Bad data length");
         }
 
-        let mut insert_pos = row_index * self.columns;
-        data.into_iter().for_each(|d| {
-            self.data.insert(insert_pos, d);
-            insert_pos += 1;
-        });
+        let mut old = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity(old.len() + rows.len() * self.columns);
+        let mut rows = rows.into_iter().peekable();
+        for r in 0..=old_rows {
+            while let Some((_, row)) = rows.next_if(|(i, _)| *i == r) {
+                data.extend(row);
+            }
+            data.extend(old.by_ref().take(self.columns));
+        }
+        self.data = data;
     }
 
-    pub fn insert_col(&mut self, col_index: usize, data: Vec<T>) {
-        if col_index > self.columns {
-            panic!("Out of bound row index");
+    // column indices refer to the grid before the insertion, columns with the same index keep their order
+    pub fn insert_cols<I: IntoIterator<Item = (usize, Vec<T>)>>(&mut self, cols: I) {
+        let mut cols = cols.into_iter().collect::<Vec<_>>();
+        cols.sort_by_key(|(i, _)| *i);
+
+        let rows = self.rows();
+        if cols.iter().any(|(i, _)| *i > self.columns) {
+            panic!("Out of bound column index");
         }
-        if data.len() != self.rows() {
+        if cols.iter().any(|(_, d)| d.len() != rows) {
             panic!("Bad data length");
         }
-        self.columns += 1;
 
-        let mut insert_pos = col_index;
-        data.into_iter().for_each(|d| {
-            self.data.insert(insert_pos, d);
-            insert_pos += self.columns;
+        let mut old = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity(old.len() + cols.len() * rows);
+        let mut cols = cols
+            .into_iter()
+            .map(|(i, d)| (i, d.into_iter()))
+            .collect::<Vec<_>>();
+        for _ in 0..rows {
+            let mut k = 0;
+            for c in 0..=self.columns {
+                while k < cols.len() && cols[k].0 == c {
+                    data.extend(cols[k].1.next());
+                    k += 1;
+                }
+                if c < self.columns {
+                    data.extend(old.next());
+                }
+            }
+        }
+        self.columns += cols.len();
+        self.data = data;
+    }
+
+    pub fn retain_rows<F: FnMut(usize) -> bool>(&mut self, keep: F) {
+        let keep = (0..self.rows()).map(keep).collect::<Vec<_>>();
+        let mut i = 0;
+        self.data.retain(|_| {
+            i += 1;
+            keep[(i - 1) / self.columns]
         });
     }
 
-    // TODO: fix -> returning a vec allows to chain operations impl Iter < impl Iter> did not work due to borrowing
-    pub fn iter_rows(&self) -> impl Iterator<Item = Vec<&T>> {
-        (0..self.rows()).map(|r| self.iter_row(r).collect())
+    pub fn retain_cols<F: FnMut(usize) -> bool>(&mut self, keep: F) {
+        let keep = (0..self.columns).map(keep).collect::<Vec<_>>();
+        let mut i = 0;
+        self.data.retain(|_| {
+            i += 1;
+            keep[(i - 1) % self.columns]
+        });
+        self.columns = keep.iter().filter(|k| **k).count();
     }
 
-    pub fn iter_columns(&self) -> impl Iterator<Item = Vec<&T>> {
-        (0..self.columns).map(|c| self.iter_col(c).collect())
+    pub fn row(&self, row_index: usize) -> Row<'_, T> {
+        if row_index >= self.rows() {
+            panic!("Out of bound row index");
+        }
+        &self.data[row_index * self.columns..(row_index + 1) * self.columns]
     }
 
-    pub fn drop_row(&mut self, row_index: usize) {
-        let row_start = row_index * self.columns;
-        let row_end = row_start + self.columns;
+    pub fn row_mut(&mut self, row_index: usize) -> RowMut<'_, T> {
+        if row_index >= self.rows() {
+            panic!("Out of bound row index");
+        }
+        &mut self.data[row_index * self.columns..(row_index + 1) * self.columns]
+    }
 
-        (row_start..row_end).rev().for_each(|i| {
-            self.data.remove(i);
-        });
+    pub fn column(&self, col_index: usize) -> Column<'_, T> {
+        if col_index >= self.columns {
+            panic!("Out of bound column index");
+        }
+        Column {
+            data: &self.data[col_index..],
+            stride: self.columns,
+            len: self.rows(),
+        }
     }
 
-    pub fn drop_column(&mut self, col_index: usize) {
+    pub fn column_mut(&mut self, col_index: usize) -> ColumnMut<'_, T> {
         if col_index >= self.columns {
             panic!("Out of bound column index");
         }
-        (col_index..self.data.len())
-            .step_by(self.columns)
-            .rev()
-            .for_each(|i| {
-                self.data.remove(i);
-            });
-        self.columns -= 1;
+        let (stride, len) = (self.columns, self.rows());
+        // SAFETY: the column cells lie within `data`, which stays mutably borrowed for the lifetime of the view
+        unsafe {
+            ColumnMut::new(
+                column_start(self.data.as_mut_ptr(), col_index, len),
+                stride,
+                len,
+            )
+        }
     }
 
-    // pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = impl Iterator<Item = &mut T>> {
-    //     (0..self.rows()).map(|r| self.iter_row_mut(r))
-    // }
+    // `chunks` can't take a zero size, but a grid without columns has no rows anyway
+    pub fn iter_rows(&self) -> impl Iterator<Item = Row<'_, T>> {
+        self.data.chunks(self.columns.max(1)).take(self.rows())
+    }
 
-    // pub fn iter_columns_mut(&mut self) -> impl Iterator<Item = impl Iterator<Item = &mut T>> {
-    //     (0..self.columns).map(|c| self.iter_col_mut(c))
-    // }
-}
+    pub fn iter_columns(&self) -> impl Iterator<Item = Column<'_, T>> {
+        (0..self.columns).map(|c| self.column(c))
+    }
 
-impl<T: Clone> Grid<T> {
-    pub fn insert_col_with(&mut self, col_index: usize, value: &T) {
-        self.insert_col(col_index, vec![value.clone(); self.rows()]);
+    pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = RowMut<'_, T>> {
+        let rows = self.rows();
+        self.data.chunks_mut(self.columns.max(1)).take(rows)
     }
-    pub fn insert_row_with(&mut self, row_index: usize, value: &T) {
-        self.insert_row(row_index, vec![value.clone(); self.columns]);
+
+    pub fn iter_columns_mut(&mut self) -> impl Iterator<Item = ColumnMut<'_, T>> {
+        let (stride, len) = (self.columns, self.rows());
+        let data = self.data.as_mut_ptr();
+        // SAFETY: every column covers a distinct set of cells, so the views never alias each other
+        (0..self.columns)
+            .map(move |c| unsafe { ColumnMut::new(column_start(data, c, len), stride, len) })
     }
-}
-impl<T: Default + Clone> Grid<T> {
-    pub fn insert_col_default(&mut self, col_index: usize) {
-        self.insert_col_with(col_index, &T::default())
+
+    pub fn drop_row(&mut self, row_index: usize) {
+        if row_index >= self.rows() {
+            panic!("Out of bound row index");
+        }
+        self.retain_rows(|r| r != row_index);
     }
-    pub fn insert_row_default(&mut self, row_index: usize) {
-        self.insert_row_with(row_index, &T::default())
+
+    pub fn drop_column(&mut self, col_index: usize) {
+        if col_index >= self.columns {
+            panic!("Out of bound column index");
+        }
+        self.retain_cols(|c| c != col_index);
     }
-}
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum ParseError {
-    InvalidGrid,
-}
+    // rebuilds the data so that the cell at a new position is taken from the old index returned by `source`
+    fn permute<F: Fn(Position) -> usize>(&mut self, columns: usize, source: F) {
+        let mut old = std::mem::take(&mut self.data)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.columns = columns;
+        self.data = (0..old.len())
+            .map(|i| old[source(self.index_to_position(i))].take().unwrap())
+            .collect();
+    }
 
-impl<T: TryFrom<char>> FromStr for Grid<T> {
-    type Err = ParseError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let columns = s
-            .lines()
-            .next()
-            .map(|l| l.len())
-            .ok_or(ParseError::InvalidGrid)?;
-        let data = s
-            .lines()
-            .flat_map(|l| l.chars().map(T::try_from))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|_| ParseError::InvalidGrid)?;
-        Ok(Grid { data, columns })
+    fn transpose_source(&self) -> (usize, impl Fn(Position) -> usize) {
+        let columns = self.columns;
+        (self.rows(), move |p: Position| p.x * columns + p.y)
     }
-}
 
-impl<T: Into<char> + Clone> fmt::Display for Grid<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let self_str = self.iter_rows().fold(String::new(), |acc, r| {
-            acc + r
-                .into_iter()
-                .cloned()
-                .map(|c| std::convert::Into::<char>::into(c))
-                .collect::<String>()
-                .as_str()
-                + "\n"
-        });
-        write!(f, "{}", &self_str)
+    fn rotate_cw_source(&self) -> (usize, impl Fn(Position) -> usize) {
+        let (columns, rows) = (self.columns, self.rows());
+        (rows, move |p: Position| (rows - 1 - p.x) * columns + p.y)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn rotate_ccw_source(&self) -> (usize, impl Fn(Position) -> usize) {
+        let (columns, rows) = (self.columns, self.rows());
+        (rows, move |p: Position| p.x * columns + columns - 1 - p.y)
+    }
 
-    #[test]
-    fn test_parse_display() {
-        let grid: Grid<char> = Grid::from_str("000\n000\n000\n").unwrap();
+    fn flip_horizontal_source(&self) -> (usize, impl Fn(Position) -> usize) {
+        let columns = self.columns;
+        (columns, move |p: Position| {
+            p.y * columns + columns - 1 - p.x
+        })
+    }
 
-        assert_eq!(grid.columns, 3);
-        assert_eq!(grid.rows(), 3);
+    fn flip_vertical_source(&self) -> (usize, impl Fn(Position) -> usize) {
+        let (columns, rows) = (self.columns, self.rows());
+        (columns, move |p: Position| (rows - 1 - p.y) * columns + p.x)
+    }
 
-        assert_eq!(grid.to_string(), "000\n000\n000\n");
+    pub fn transpose(&mut self) {
+        let (columns, source) = self.transpose_source();
+        self.permute(columns, source);
     }
 
-    #[test]
-    fn test_insert_row() {
-        let mut grid: Grid<char> = Grid::from_str("000\n000\n000\n").unwrap();
-        grid.insert_row(1, vec!['1', '2', '3']);
-        assert_eq!(grid.to_string(), "000\n123\n000\n000\n");
+    pub fn rotate_cw(&mut self) {
+        let (columns, source) = self.rotate_cw_source();
+        self.permute(columns, source);
     }
-    #[test]
-    fn test_insert_col() {
-        let mut grid: Grid<char> = Grid::from_str("000\n000\n000\n").unwrap();
-        grid.insert_col(1, vec!['1', '2', '3']);
-        assert_eq!(grid.to_string(), "0100\n0200\n0300\n");
-        assert_eq!(grid.rows(), 3);
-        assert_eq!(grid.columns, 4);
 
-        grid.insert_col(4, "456".chars().collect());
-        assert_eq!(grid.to_string(), "01004\n02005\n03006\n");
-        assert_eq!(grid.rows(), 3);
-        assert_eq!(grid.columns, 5);
+    pub fn rotate_ccw(&mut self) {
+        let (columns, source) = self.rotate_ccw_source();
+        self.permute(columns, source);
     }
 
-    #[test]
-    fn test_iter_cols() {
-        let grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+    // mirrors left to right
+    pub fn flip_horizontal(&mut self) {
+        self.iter_rows_mut().for_each(|row| row.reverse());
+    }
+
+    // mirrors top to bottom
+    pub fn flip_vertical(&mut self) {
+        let (columns, source) = self.flip_vertical_source();
+        self.permute(columns, source);
+    }
+
+    pub fn flood_fill<P: FnMut(&T) -> bool>(
+        &self,
+        start: &Position,
+        mut passable: P,
+    ) -> Vec<Position> {
+        let mut visited = vec![false; self.data.len()];
+        let mut filled = vec![];
+        let mut queue = VecDeque::new();
+        if let Some(i) = self.position_to_index(start) {
+            if passable(&self.data[i]) {
+                visited[i] = true;
+                queue.push_back(*start);
+            }
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            filled.push(pos);
+            for (_, p, t) in self.neighbors4(&pos) {
+                let i = self.columns * p.y + p.x;
+                if !visited[i] && passable(t) {
+                    visited[i] = true;
+                    queue.push_back(p);
+                }
+            }
+        }
+        filled
+    }
+
+    // breadth first distances from the closest source, `None` for cells that can't be reached
+    pub fn distance_field<I, P>(&self, sources: I, mut passable: P) -> Grid<Option<usize>>
+    where
+        I: IntoIterator<Item = Position>,
+        P: FnMut(&T) -> bool,
+    {
+        let mut distances = vec![None; self.data.len()];
+        let mut queue = VecDeque::new();
+        for source in sources {
+            if let Some(i) = self.position_to_index(&source) {
+                if distances[i].is_none() && passable(&self.data[i]) {
+                    distances[i] = Some(0);
+                    queue.push_back((source, 0));
+                }
+            }
+        }
+
+        while let Some((pos, distance)) = queue.pop_front() {
+            for (_, p, t) in self.neighbors4(&pos) {
+                let i = self.columns * p.y + p.x;
+                if distances[i].is_none() && passable(t) {
+                    distances[i] = Some(distance + 1);
+                    queue.push_back((p, distance + 1));
+                }
+            }
+        }
+        Grid::new(distances, self.columns)
+    }
+
+    // neighbouring cells end up in the same region when `equivalent` holds for them
+    pub fn label_regions<F: FnMut(&T, &T) -> bool>(
+        &self,
+        mut equivalent: F,
+    ) -> (Grid<RegionId>, Vec<Region>) {
+        let mut labels: Vec<Option<RegionId>> = vec![None; self.data.len()];
+        let mut regions = vec![];
+        let mut queue = VecDeque::new();
+
+        for start in 0..self.data.len() {
+            if labels[start].is_some() {
+                continue;
+            }
+            let id = RegionId(regions.len());
+            let start_pos = self.index_to_position(start);
+            let mut region = Region {
+                id,
+                area: 0,
+                perimeter: 0,
+                min: start_pos,
+                max: start_pos,
+                touches_border: false,
+            };
+
+            labels[start] = Some(id);
+            queue.push_back(start_pos);
+            while let Some(pos) = queue.pop_front() {
+                let cell = &self.data[self.columns * pos.y + pos.x];
+                let mut same_region_neighbors = 0;
+                for (_, p, t) in self.neighbors4(&pos) {
+                    let i = self.columns * p.y + p.x;
+                    match labels[i] {
+                        Some(label) if label == id => same_region_neighbors += 1,
+                        None if equivalent(cell, t) => {
+                            labels[i] = Some(id);
+                            same_region_neighbors += 1;
+                            queue.push_back(p);
+                        }
+                        _ => (),
+                    }
+                }
+
+                region.area += 1;
+                region.perimeter += 4 - same_region_neighbors;
+                region.min = Position {
+                    x: region.min.x.min(pos.x),
+                    y: region.min.y.min(pos.y),
+                };
+                region.max = Position {
+                    x: region.max.x.max(pos.x),
+                    y: region.max.y.max(pos.y),
+                };
+                region.touches_border |= pos.x == 0
+                    || pos.y == 0
+                    || pos.x == self.columns - 1
+                    || pos.y == self.rows() - 1;
+            }
+            regions.push(region);
+        }
+
+        let labels = Grid::new(labels.into_iter().flatten().collect(), self.columns);
+        (labels, regions)
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid::new(self.data.iter().map(f).collect(), self.columns)
+    }
+
+    pub fn map_with_pos<U, F: FnMut(Position, &T) -> U>(&self, mut f: F) -> Grid<U> {
+        let data = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, t)| f(self.index_to_position(i), t))
+            .collect();
+        Grid::new(data, self.columns)
+    }
+
+    pub fn zip_with<U, V, F: FnMut(&T, &U) -> V>(&self, other: &Grid<U>, mut f: F) -> Grid<V> {
+        if self.columns != other.columns || self.rows() != other.rows() {
+            panic!("Mismatching grid sizes");
+        }
+        let data = self
+            .data
+            .iter()
+            .zip(other.data.iter())
+            .map(|(a, b)| f(a, b))
+            .collect();
+        Grid::new(data, self.columns)
+    }
+}
+
+impl Grid<Option<usize>> {
+    pub fn reachable_within(&self, steps: usize) -> usize {
+        self.iter().flatten().filter(|d| **d <= steps).count()
+    }
+
+    // a cell reached after `d` steps can be revisited every two steps, so only the parity has to match,
+    // except for a source without reachable neighbours which can't be left at all
+    pub fn reachable_in_exactly(&self, steps: usize) -> usize {
+        self.find_positions(|d| d.is_some_and(|d| d <= steps && d % 2 == steps % 2))
+            .filter(|p| {
+                steps == 0 || self[*p] != Some(0) || self.neighbors4(p).any(|(_, _, d)| d.is_some())
+            })
+            .count()
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(columns: usize, rows: usize, value: T) -> Self {
+        Grid::new(vec![value; columns * rows], columns)
+    }
+
+    pub fn insert_col_with(&mut self, col_index: usize, value: &T) {
+        self.insert_cols_with(&[col_index], value);
+    }
+    pub fn insert_row_with(&mut self, row_index: usize, value: &T) {
+        self.insert_rows_with(&[row_index], value);
+    }
+
+    pub fn insert_cols_with(&mut self, col_indices: &[usize], value: &T) {
+        let rows = self.rows();
+        self.insert_cols(col_indices.iter().map(|c| (*c, vec![value.clone(); rows])));
+    }
+    pub fn insert_rows_with(&mut self, row_indices: &[usize], value: &T) {
+        let columns = self.columns;
+        self.insert_rows(
+            row_indices
+                .iter()
+                .map(|r| (*r, vec![value.clone(); columns])),
+        );
+    }
+
+    pub fn pad(&mut self, top: usize, right: usize, bottom: usize, left: usize, value: &T) {
+        let columns = left + self.columns + right;
+        let rows = top + self.rows() + bottom;
+
+        let mut old = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity(columns * rows);
+        data.resize(top * columns, value.clone());
+        for _ in 0..rows - top - bottom {
+            data.extend(std::iter::repeat_n(value, left).cloned());
+            data.extend(old.by_ref().take(self.columns));
+            data.extend(std::iter::repeat_n(value, right).cloned());
+        }
+        data.resize(columns * rows, value.clone());
+
+        self.columns = columns;
+        self.data = data;
+    }
+
+    fn permuted<F: Fn(Position) -> usize>(&self, columns: usize, source: F) -> Grid<T> {
+        let mut grid = Grid::new(vec![], columns);
+        grid.data = (0..self.data.len())
+            .map(|i| self.data[source(grid.index_to_position(i))].clone())
+            .collect();
+        grid
+    }
+
+    pub fn transposed(&self) -> Grid<T> {
+        let (columns, source) = self.transpose_source();
+        self.permuted(columns, source)
+    }
+
+    pub fn rotated_cw(&self) -> Grid<T> {
+        let (columns, source) = self.rotate_cw_source();
+        self.permuted(columns, source)
+    }
+
+    pub fn rotated_ccw(&self) -> Grid<T> {
+        let (columns, source) = self.rotate_ccw_source();
+        self.permuted(columns, source)
+    }
+
+    pub fn flipped_horizontal(&self) -> Grid<T> {
+        let (columns, source) = self.flip_horizontal_source();
+        self.permuted(columns, source)
+    }
+
+    pub fn flipped_vertical(&self) -> Grid<T> {
+        let (columns, source) = self.flip_vertical_source();
+        self.permuted(columns, source)
+    }
+}
+
+impl<T: Default + Clone> Grid<T> {
+    pub fn insert_col_default(&mut self, col_index: usize) {
+        self.insert_col_with(col_index, &T::default())
+    }
+    pub fn insert_row_default(&mut self, row_index: usize) {
+        self.insert_row_with(row_index, &T::default())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash, PartialOrd, Ord)]
+pub struct RegionId(pub usize);
+
+// `perimeter` counts the cell edges facing another region or the outside of the grid,
+// `min` and `max` are the corners of the bounding box
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Region {
+    pub id: RegionId,
+    pub area: usize,
+    pub perimeter: usize,
+    pub min: Position,
+    pub max: Position,
+    pub touches_border: bool,
+}
+
+pub type Row<'a, T> = &'a [T];
+pub type RowMut<'a, T> = &'a mut [T];
+
+pub struct Column<'a, T> {
+    data: &'a [T],
+    stride: usize,
+    len: usize,
+}
+
+impl<'a, T> Column<'a, T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        (index < self.len).then(|| &self.data[index * self.stride])
+    }
+
+    pub fn iter(&self) -> StepBy<slice::Iter<'a, T>> {
+        self.data.iter().step_by(self.stride)
+    }
+}
+
+impl<T> Clone for Column<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Column<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Column<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+    fn index(&self, pos: Position) -> &Self::Output {
+        self.at(&pos).expect("Out of bound position")
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut Self::Output {
+        self.at_mut(&pos).expect("Out of bound position")
+    }
+}
+
+impl<T> Index<usize> for Column<'_, T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bound column index")
+    }
+}
+
+impl<'a, T> IntoIterator for Column<'a, T> {
+    type Item = &'a T;
+    type IntoIter = StepBy<slice::Iter<'a, T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Column<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq> Eq for Column<'_, T> {}
+
+impl<T: PartialEq> PartialEq<[T]> for Column<'_, T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.len == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+// strided mutable view, unlike `iter_col_mut` several of them can be alive at the same time
+// SAFETY: `data` must point to the first of `rows` rows with more than `col_index` cells each,
+// without any rows it may be the dangling pointer of an empty `Vec`, which can't be offset
+unsafe fn column_start<T>(data: *mut T, col_index: usize, rows: usize) -> *mut T {
+    if rows == 0 {
+        NonNull::dangling().as_ptr()
+    } else {
+        data.add(col_index)
+    }
+}
+
+pub struct ColumnMut<'a, T> {
+    data: *mut T,
+    stride: usize,
+    len: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+// SAFETY: a column view behaves like a set of `&mut T`
+unsafe impl<T: Send> Send for ColumnMut<'_, T> {}
+unsafe impl<T: Sync> Sync for ColumnMut<'_, T> {}
+
+impl<'a, T> ColumnMut<'a, T> {
+    // SAFETY: `data` must point to `len` valid cells spaced `stride` apart which are not accessed through
+    // anything else for 'a
+    unsafe fn new(data: *mut T, stride: usize, len: usize) -> Self {
+        ColumnMut {
+            data,
+            stride,
+            len,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        // SAFETY: the index is in bounds, see `new`
+        (index < self.len).then(|| unsafe { &*self.data.add(index * self.stride) })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        // SAFETY: the index is in bounds and `&mut self` guarantees exclusive access
+        (index < self.len).then(|| unsafe { &mut *self.data.add(index * self.stride) })
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a >= self.len || b >= self.len {
+            panic!("Out of bound column index");
+        }
+        // SAFETY: both indices are in bounds, `ptr::swap` allows them to be equal
+        unsafe {
+            std::ptr::swap(
+                self.data.add(a * self.stride),
+                self.data.add(b * self.stride),
+            )
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len).map(|i| &self[i])
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let (data, stride) = (self.data, self.stride);
+        // SAFETY: every index is visited once, so the returned references never alias
+        (0..self.len).map(move |i| unsafe { &mut *data.add(i * stride) })
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ColumnMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Index<usize> for ColumnMut<'_, T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Out of bound column index")
+    }
+}
+
+impl<T> IndexMut<usize> for ColumnMut<'_, T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Out of bound column index")
+    }
+}
+
+// grid with signed coordinates that grows with every write, cells never written read as `default`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<IntPosition, T>,
+    default: T,
+    bounds: Option<(IntPosition, IntPosition)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn at(&self, pos: &IntPosition) -> &T {
+        self.cells.get(pos).unwrap_or(&self.default)
+    }
+
+    pub fn is_set(&self, pos: &IntPosition) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    pub fn insert(&mut self, pos: IntPosition, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                IntPosition {
+                    x: min.x.min(pos.x),
+                    y: min.y.min(pos.y),
+                },
+                IntPosition {
+                    x: max.x.max(pos.x),
+                    y: max.y.max(pos.y),
+                },
+            ),
+            None => (pos, pos),
+        });
+        self.cells.insert(pos, value)
+    }
+
+    pub fn min(&self) -> Option<IntPosition> {
+        self.bounds.map(|(min, _)| min)
+    }
+
+    pub fn max(&self) -> Option<IntPosition> {
+        self.bounds.map(|(_, max)| max)
+    }
+
+    pub fn columns(&self) -> usize {
+        self.bounds
+            .map_or(0, |(min, max)| max.x.abs_diff(min.x) + 1)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.bounds
+            .map_or(0, |(min, max)| max.y.abs_diff(min.y) + 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&IntPosition, &T)> {
+        self.cells.iter()
+    }
+
+    // maps a signed position onto the dense grid returned by `to_grid`
+    pub fn to_grid_position(&self, pos: &IntPosition) -> Option<Position> {
+        let (min, max) = self.bounds?;
+        if pos.x < min.x || pos.y < min.y || pos.x > max.x || pos.y > max.y {
+            return None;
+        }
+        Some(Position {
+            x: pos.x.abs_diff(min.x),
+            y: pos.y.abs_diff(min.y),
+        })
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    pub fn at_mut(&mut self, pos: &IntPosition) -> &mut T {
+        if !self.is_set(pos) {
+            self.insert(*pos, self.default.clone());
+        }
+        self.cells.get_mut(pos).unwrap()
+    }
+
+    pub fn to_grid(&self) -> Grid<T> {
+        let Some((min, _)) = self.bounds else {
+            return Grid::new(vec![], 0);
+        };
+        Grid::from_fn(self.columns(), self.rows(), |p| {
+            self.at(&(min + (p.x as isize, p.y as isize))).clone()
+        })
+    }
+}
+
+impl<T: Default> From<Grid<T>> for SparseGrid<T> {
+    fn from(value: Grid<T>) -> Self {
+        let columns = value.columns;
+        let mut sparse = SparseGrid::new(T::default());
+        value.data.into_iter().enumerate().for_each(|(i, t)| {
+            let (y, x) = num::Integer::div_mod_floor(&i, &columns);
+            sparse.insert(IntPosition::from(Position { x, y }), t);
+        });
+        sparse
+    }
+}
+
+impl<T: Clone> From<&SparseGrid<T>> for Grid<T> {
+    fn from(value: &SparseGrid<T>) -> Self {
+        value.to_grid()
+    }
+}
+
+// lines and columns are counted from 1 to match what an editor shows
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError<E> {
+    EmptyGrid,
+    InconsistentRowLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        line: usize,
+        column: usize,
+        character: char,
+        error: E,
+    },
+}
+
+impl<E: fmt::Debug> fmt::Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::EmptyGrid => write!(f, "empty grid"),
+            ParseError::InconsistentRowLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} has {found} columns, expected {expected} like the first line"
+            ),
+            ParseError::InvalidCell {
+                line,
+                column,
+                character,
+                error,
+            } => write!(
+                f,
+                "invalid character {character:?} at line {line}, column {column}: {error:?}"
+            ),
+        }
+    }
+}
+
+impl<E: fmt::Debug> std::error::Error for ParseError<E> {}
+
+impl<T: TryFrom<char>> FromStr for Grid<T> {
+    type Err = ParseError<T::Error>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .collect::<Vec<_>>();
+        let line_count = lines
+            .iter()
+            .rposition(|l| !l.is_empty())
+            .map_or(0, |i| i + 1);
+        let lines = &lines[..line_count];
+
+        let columns = lines
+            .first()
+            .map(|l| l.chars().count())
+            .ok_or(ParseError::EmptyGrid)?;
+
+        let mut data = Vec::with_capacity(columns * lines.len());
+        for (y, l) in lines.iter().enumerate() {
+            let found = l.chars().count();
+            if found != columns {
+                return Err(ParseError::InconsistentRowLength {
+                    line: y + 1,
+                    expected: columns,
+                    found,
+                });
+            }
+            for (x, c) in l.chars().enumerate() {
+                let cell = T::try_from(c).map_err(|error| ParseError::InvalidCell {
+                    line: y + 1,
+                    column: x + 1,
+                    character: c,
+                    error,
+                })?;
+                data.push(cell);
+            }
+        }
+        Ok(Grid { data, columns })
+    }
+}
+
+impl<T: Into<char> + Clone> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let self_str = self.iter_rows().fold(String::new(), |acc, r| {
+            acc + r
+                .iter()
+                .cloned()
+                .map(|c| std::convert::Into::<char>::into(c))
+                .collect::<String>()
+                .as_str()
+                + "\n"
+        });
+        write!(f, "{}", &self_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_display() {
+        let grid: Grid<char> = Grid::from_str("000\n000\n000\n").unwrap();
+
+        assert_eq!(grid.columns, 3);
+        assert_eq!(grid.rows(), 3);
+
+        assert_eq!(grid.to_string(), "000\n000\n000\n");
+    }
+
+    #[test]
+    fn test_parse_line_endings() {
+        let grid: Grid<char> = Grid::from_str("12\r\n34\r\n\n\n").unwrap();
+        assert_eq!(grid.columns, 2);
+        assert_eq!(grid.rows(), 2);
+        assert_eq!(grid.to_string(), "12\n34\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Grid::<char>::from_str("\n\n"), Err(ParseError::EmptyGrid));
+        assert_eq!(
+            Grid::<char>::from_str("123\n45\n678"),
+            Err(ParseError::InconsistentRowLength {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Grid::<char>::from_str("123\n\n456"),
+            Err(ParseError::InconsistentRowLength {
+                line: 2,
+                expected: 3,
+                found: 0
+            })
+        );
+
+        let err = Grid::<u8>::from_str("ab\nc€").unwrap_err();
+        let ParseError::InvalidCell {
+            line,
+            column,
+            character,
+            error,
+        } = err
+        else {
+            panic!("unexpected error {:?}", err);
+        };
+        assert_eq!((line, column, character), (2, 2, '€'));
+        assert_eq!(error, u8::try_from('€').unwrap_err());
+        assert_eq!(
+            err.to_string(),
+            format!("invalid character '€' at line 2, column 2: {:?}", error)
+        );
+    }
+
+    #[test]
+    fn test_insert_row() {
+        let mut grid: Grid<char> = Grid::from_str("000\n000\n000\n").unwrap();
+        grid.insert_row(1, vec!['1', '2', '3']);
+        assert_eq!(grid.to_string(), "000\n123\n000\n000\n");
+    }
+    #[test]
+    fn test_insert_col() {
+        let mut grid: Grid<char> = Grid::from_str("000\n000\n000\n").unwrap();
+        grid.insert_col(1, vec!['1', '2', '3']);
+        assert_eq!(grid.to_string(), "0100\n0200\n0300\n");
+        assert_eq!(grid.rows(), 3);
+        assert_eq!(grid.columns, 4);
+
+        grid.insert_col(4, "456".chars().collect());
+        assert_eq!(grid.to_string(), "01004\n02005\n03006\n");
+        assert_eq!(grid.rows(), 3);
+        assert_eq!(grid.columns, 5);
+    }
+
+    #[test]
+    fn test_insert_rows() {
+        let mut grid: Grid<char> = Grid::from_str("000\n111\n").unwrap();
+        grid.insert_rows([
+            (2, vec!['a', 'a', 'a']),
+            (0, vec!['b', 'b', 'b']),
+            (1, vec!['c', 'c', 'c']),
+            (0, vec!['d', 'd', 'd']),
+        ]);
+        assert_eq!(grid.to_string(), "bbb\nddd\n000\nccc\n111\naaa\n");
+
+        grid.insert_rows_with(&[0, 6], &'x');
+        assert_eq!(grid.rows(), 8);
+        assert_eq!(grid.iter_row(0).collect::<String>(), "xxx");
+        assert_eq!(grid.iter_row(7).collect::<String>(), "xxx");
+    }
+
+    #[test]
+    fn test_insert_cols() {
+        let mut grid: Grid<char> = Grid::from_str("012\n345\n").unwrap();
+        grid.insert_cols([(3, vec!['a', 'b']), (1, vec!['c', 'd'])]);
+        assert_eq!(grid.to_string(), "0c12a\n3d45b\n");
+        assert_eq!(grid.columns, 5);
+
+        grid.insert_cols_with(&[0, 0, 2], &'x');
+        assert_eq!(grid.to_string(), "xx0cx12a\nxx3dx45b\n");
+        assert_eq!(grid.columns, 8);
+        assert_eq!(grid.rows(), 2);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+        grid.retain_rows(|r| r != 0);
+        assert_eq!(grid.to_string(), "456\n789\n");
+
+        grid.retain_cols(|c| c == 1);
+        assert_eq!(grid.to_string(), "5\n8\n");
+        assert_eq!(grid.columns, 1);
+    }
+
+    #[test]
+    fn test_pad() {
+        let mut grid: Grid<char> = Grid::from_str("12\n34\n").unwrap();
+        grid.pad(1, 2, 0, 1, &'.');
+        assert_eq!(grid.to_string(), ".....\n.12..\n.34..\n");
+        assert_eq!(grid.columns, 5);
+
+        grid.pad(0, 0, 2, 0, &'o');
+        assert_eq!(grid.to_string(), ".....\n.12..\n.34..\nooooo\nooooo\n");
+    }
+
+    #[test]
+    fn test_iter_cols() {
+        let grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
         let mut iter = grid.iter_columns();
 
         assert_eq!(iter.next().unwrap().into_iter().collect::<String>(), "147");
@@ -337,9 +1632,9 @@ mod tests {
         let grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
         let mut iter = grid.iter_rows();
 
-        assert_eq!(iter.next().unwrap().into_iter().collect::<String>(), "123");
-        assert_eq!(iter.next().unwrap().into_iter().collect::<String>(), "456");
-        assert_eq!(iter.next().unwrap().into_iter().collect::<String>(), "789");
+        assert_eq!(iter.next().unwrap().iter().collect::<String>(), "123");
+        assert_eq!(iter.next().unwrap().iter().collect::<String>(), "456");
+        assert_eq!(iter.next().unwrap().iter().collect::<String>(), "789");
         assert!(iter.next().is_none());
     }
 
@@ -356,10 +1651,66 @@ mod tests {
         let f = grid
             .iter_rows()
             .enumerate()
-            .find(|(i, v)| v.iter().all(|e| e == &&'0'));
+            .find(|(i, v)| v.iter().all(|e| e == &'0'));
         assert_eq!(f.unwrap().0, 1);
     }
 
+    #[test]
+    fn test_column_view() {
+        let grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+        let col = grid.column(1);
+
+        assert_eq!(col.len(), 3);
+        assert_eq!(col[2], '8');
+        assert_eq!(col.get(3), None);
+        assert_eq!(col, grid.column(1));
+        assert_ne!(col, grid.column(2));
+        assert_eq!(col, ['2', '5', '8'][..]);
+        assert_eq!(grid.row(1), &['4', '5', '6']);
+        assert_eq!(format!("{:?}", col), "['2', '5', '8']");
+    }
+
+    #[test]
+    fn test_iter_rows_mut() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+        grid.iter_rows_mut().for_each(|r| r.reverse());
+        assert_eq!(grid.to_string(), "321\n654\n987\n");
+
+        grid.row_mut(0).fill('x');
+        assert_eq!(grid.to_string(), "xxx\n654\n987\n");
+    }
+
+    #[test]
+    fn test_iter_columns_mut() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+
+        let mut columns = grid.iter_columns_mut().collect::<Vec<_>>();
+        let (first, rest) = columns.split_at_mut(1);
+        std::mem::swap(&mut first[0][0], &mut rest[1][2]);
+        rest[0].swap(0, 2);
+        rest[0].iter_mut().for_each(|c| *c = c.to_ascii_uppercase());
+        assert_eq!(grid.to_string(), "983\n456\n721\n");
+
+        let mut col = grid.column_mut(1);
+        col[1] = 'x';
+        assert_eq!(col.iter().collect::<String>(), "8x2");
+        assert_eq!(grid.to_string(), "983\n4x6\n721\n");
+    }
+
+    #[test]
+    fn test_views_without_rows() {
+        let mut grid = Grid::<u64>::filled(4, 0, 0);
+        assert_eq!(grid.iter_columns_mut().filter(|c| c.is_empty()).count(), 4);
+        assert!(grid.column_mut(3).is_empty());
+        assert_eq!(grid.iter_rows().count(), 0);
+
+        let mut grid = Grid::<char>::new(vec![], 0);
+        assert_eq!(grid.iter_rows().count(), 0);
+        assert_eq!(grid.iter_rows_mut().count(), 0);
+        assert_eq!(grid.iter_columns_mut().count(), 0);
+        assert_eq!(grid.to_string(), "");
+    }
+
     #[test]
     fn test_drop_row() {
         let mut grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
@@ -378,6 +1729,495 @@ mod tests {
         assert_eq!(grid.columns, 2);
     }
 
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new('.');
+        assert!(grid.is_empty());
+        assert_eq!(grid.to_grid(), Grid::new(vec![], 0));
+        assert_eq!(grid.to_grid().to_string(), "");
+
+        let mut pos = IntPosition { x: 0, y: 0 };
+        grid.insert(pos, '#');
+        for dir in [Direction::West, Direction::West, Direction::North] {
+            pos = pos.move_dir(&dir);
+            grid.insert(pos, '#');
+        }
+        *grid.at_mut(&IntPosition { x: 1, y: 1 }) = 'o';
+
+        assert_eq!(grid.min(), Some(IntPosition { x: -2, y: -1 }));
+        assert_eq!(grid.max(), Some(IntPosition { x: 1, y: 1 }));
+        assert_eq!(grid.columns(), 4);
+        assert_eq!(grid.rows(), 3);
+        assert_eq!(grid.len(), 5);
+        assert_eq!(grid.at(&IntPosition { x: 5, y: -7 }), &'.');
+        assert_eq!(
+            grid.to_grid_position(&IntPosition { x: 0, y: 0 }),
+            Some(Position { x: 2, y: 1 })
+        );
+        assert_eq!(grid.to_grid_position(&IntPosition { x: 2, y: 0 }), None);
+
+        assert_eq!(grid.to_grid().to_string(), "#...\n###.\n...o\n");
+    }
+
+    #[test]
+    fn test_sparse_grid_from_grid() {
+        let grid: Grid<char> = Grid::from_str("123\n456\n").unwrap();
+        let sparse = SparseGrid::from(grid.clone());
+
+        assert_eq!(sparse.at(&IntPosition { x: 2, y: 1 }), &'6');
+        assert_eq!(sparse.at(&IntPosition { x: -1, y: 0 }), &char::default());
+        assert_eq!(Grid::from(&sparse), grid);
+    }
+
+    #[test]
+    fn test_neighbors4() {
+        let grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+
+        let corner = grid
+            .neighbors4(&Position { x: 0, y: 0 })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            corner,
+            vec![
+                (Direction::East, Position { x: 1, y: 0 }, &'2'),
+                (Direction::South, Position { x: 0, y: 1 }, &'4'),
+            ]
+        );
+
+        let center = grid
+            .neighbors4(&Position { x: 1, y: 1 })
+            .map(|(_, _, c)| c)
+            .collect::<String>();
+        assert_eq!(center, "2684");
+
+        let edge = grid
+            .neighbors4(&Position { x: 2, y: 2 })
+            .map(|(d, _, _)| d)
+            .collect::<Vec<_>>();
+        assert_eq!(edge, vec![Direction::North, Direction::West]);
+    }
+
+    #[test]
+    fn test_direction_turns() {
+        for d in Direction::all() {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.invert());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::West.delta(), (-1, 0));
+
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthEast.invert(), Direction8::NorthWest);
+        assert!(Direction8::SouthWest.is_diagonal());
+        assert_eq!(
+            Direction8::all()
+                .iter()
+                .filter(|d| !d.is_diagonal())
+                .count(),
+            4
+        );
+        assert_eq!(Direction::try_from(Direction8::East), Ok(Direction::East));
+        assert!(Direction::try_from(Direction8::NorthEast).is_err());
+    }
+
+    #[test]
+    fn test_direction_parse() {
+        for (chars, expected) in [
+            ("UN^", Direction::North),
+            ("RE>", Direction::East),
+            ("DSv", Direction::South),
+            ("LW<", Direction::West),
+        ] {
+            for c in chars.chars() {
+                assert_eq!(Direction::try_from(c), Ok(expected));
+            }
+        }
+        assert_eq!(Direction::from_str("L"), Ok(Direction::West));
+        assert!(Direction::from_str("LR").is_err());
+        assert!(Direction::try_from('x').is_err());
+
+        assert_eq!(Direction::from_hex_digit('0'), Ok(Direction::East));
+        assert_eq!(Direction::from_hex_digit('3'), Ok(Direction::North));
+        assert_eq!(
+            Direction::from_hex_digit('4'),
+            Err(InvalidDirection("4".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_position_arithmetic() {
+        let pos = Position { x: 3, y: 2 };
+        assert_eq!(pos + (2, -2), Position { x: 5, y: 0 });
+        assert_eq!(pos - (3, -1), Position { x: 0, y: 3 });
+        assert_eq!(pos.checked_add_signed((-4, 0)), None);
+        assert_eq!(
+            pos.move_by(&Direction::South, 5),
+            Some(Position { x: 3, y: 7 })
+        );
+        assert_eq!(pos.move_by(&Direction::North, 3), None);
+
+        let pos = IntPosition { x: 1, y: -1 };
+        assert_eq!(pos + (-3, 2), IntPosition { x: -2, y: 1 });
+        assert_eq!(pos - (1, 1), IntPosition { x: 0, y: -2 });
+        assert_eq!(
+            pos.move_by(&Direction::West, 4),
+            IntPosition { x: -3, y: -1 }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_position_add_negative() {
+        let _ = Position { x: 0, y: 0 } + (0, -1);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid: Grid<char> = Grid::from_str("a.#.\n....\n#..b\n").unwrap();
+
+        let ray = grid
+            .ray(&Position { x: 0, y: 0 }, Direction::East)
+            .map(|(_, c)| c)
+            .collect::<String>();
+        assert_eq!(ray, ".#.");
+        assert_eq!(
+            grid.ray(&Position { x: 3, y: 2 }, Direction::North)
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>(),
+            vec![Position { x: 3, y: 1 }, Position { x: 3, y: 0 }]
+        );
+        assert_eq!(
+            grid.ray(&Position { x: 3, y: 2 }, Direction::East).count(),
+            0
+        );
+
+        assert_eq!(
+            grid.cast_until(&Position { x: 0, y: 0 }, Direction::East, |c| c == &'#'),
+            Some((Position { x: 2, y: 0 }, 2))
+        );
+        assert_eq!(
+            grid.cast_until(&Position { x: 0, y: 0 }, Direction::South, |c| c == &'#'),
+            Some((Position { x: 0, y: 2 }, 2))
+        );
+        assert_eq!(
+            grid.cast_until(&Position { x: 1, y: 1 }, Direction::West, |c| c == &'#'),
+            None
+        );
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let grid: Grid<char> = Grid::from_str("...#\n.#..\n.#.#\n").unwrap();
+        let distances = grid.distance_field([Position { x: 0, y: 0 }], |c| c != &'#');
+
+        assert_eq!(distances.at(&Position { x: 0, y: 2 }), Some(&Some(2)));
+        assert_eq!(distances.at(&Position { x: 2, y: 2 }), Some(&Some(4)));
+        assert_eq!(distances.at(&Position { x: 1, y: 1 }), Some(&None));
+        assert_eq!(distances.reachable_within(3), 6);
+        assert_eq!(distances.reachable_in_exactly(3), 3);
+
+        let distances = grid
+            .distance_field([Position { x: 0, y: 2 }, Position { x: 3, y: 1 }], |c| {
+                c != &'#'
+            });
+        assert_eq!(distances.at(&Position { x: 2, y: 0 }), Some(&Some(2)));
+        assert_eq!(distances.reachable_within(0), 2);
+    }
+
+    // the example garden of 2023 day21
+    const GARDEN: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test]
+    fn test_reachable_in_exactly_garden() {
+        let grid: Grid<char> = Grid::from_str(GARDEN).unwrap();
+        let start = grid.find_pos(|c| c == &'S').unwrap();
+        let distances = grid.distance_field([start], |c| c != &'#');
+
+        assert_eq!(distances.reachable_in_exactly(1), 2);
+        assert_eq!(distances.reachable_in_exactly(2), 4);
+        assert_eq!(distances.reachable_in_exactly(6), 16);
+    }
+
+    #[test]
+    fn test_reachable_in_exactly_walled_in() {
+        let grid: Grid<char> = Grid::from_str(".#.\n#..\n").unwrap();
+        let distances = grid.distance_field([Position { x: 0, y: 0 }], |c| c != &'#');
+
+        assert_eq!(distances.reachable_in_exactly(0), 1);
+        assert_eq!(distances.reachable_in_exactly(2), 0);
+        assert_eq!(distances.reachable_within(2), 1);
+    }
+
+    #[test]
+    fn test_constructors() {
+        let grid = Grid::filled(3, 2, '.');
+        assert_eq!(grid.to_string(), "...\n...\n");
+
+        let grid = Grid::from_fn(3, 2, |p| {
+            char::from_digit((p.y * 3 + p.x) as u32, 10).unwrap()
+        });
+        assert_eq!(grid.to_string(), "012\n345\n");
+
+        let grid = Grid::from_rows(["ab".chars(), "cd".chars(), "ef".chars()]);
+        assert_eq!(grid.columns, 2);
+        assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+
+        let grid: Grid<char> = Grid::from_rows(Vec::<Vec<char>>::new());
+        assert_eq!(grid.rows(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_rows_inconsistent() {
+        Grid::from_rows([vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_map_and_zip() {
+        let grid: Grid<char> = Grid::from_str("1.3\n.5.\n").unwrap();
+
+        let digits = grid.map(|c| c.to_digit(10).unwrap_or(0));
+        assert_eq!(digits.at(&Position { x: 2, y: 0 }), Some(&3));
+
+        let marked = grid.map_with_pos(|p, c| if p.x == p.y { '#' } else { *c });
+        assert_eq!(marked.to_string(), "#.3\n.#.\n");
+
+        let sums = digits.zip_with(&digits.map_with_pos(|p, _| p.x as u32), |a, b| a + b);
+        assert_eq!(
+            sums.iter().copied().collect::<Vec<_>>(),
+            vec![1, 1, 5, 0, 6, 2]
+        );
+    }
+
+    #[test]
+    fn test_step_and_index() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n").unwrap();
+
+        assert!(grid.is_position_valid(&Position { x: 2, y: 1 }));
+        assert!(!grid.is_position_valid(&Position { x: 0, y: 2 }));
+
+        let corner = Position { x: 2, y: 1 };
+        assert_eq!(grid.step(&corner, &Direction::East), None);
+        assert_eq!(grid.step(&corner, &Direction::South), None);
+        assert_eq!(
+            grid.step(&corner, &Direction::North),
+            Some(Position { x: 2, y: 0 })
+        );
+
+        assert_eq!(grid[corner], '6');
+        grid[Position { x: 0, y: 0 }] = 'x';
+        assert_eq!(grid.to_string(), "x23\n456\n");
+    }
+
+    #[test]
+    fn test_neighbors8() {
+        let grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+
+        let center = grid
+            .neighbors8(&Position { x: 1, y: 1 })
+            .map(|(_, _, c)| c)
+            .collect::<String>();
+        assert_eq!(center, "23698741");
+
+        let corner = grid
+            .neighbors8(&Position { x: 2, y: 0 })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            corner,
+            vec![
+                (Direction8::South, Position { x: 2, y: 1 }, &'6'),
+                (Direction8::SouthWest, Position { x: 1, y: 1 }, &'5'),
+                (Direction8::West, Position { x: 1, y: 0 }, &'2'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_neighbors_mut() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
+
+        let dirs = grid
+            .neighbors4_mut(&Position { x: 1, y: 1 })
+            .map(|(d, _, c)| {
+                *c = 'X';
+                d
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dirs,
+            vec![
+                Direction::North,
+                Direction::East,
+                Direction::South,
+                Direction::West
+            ]
+        );
+        assert_eq!(grid.to_string(), "1X3\nX5X\n7X9\n");
+
+        grid.neighbors8_mut(&Position { x: 0, y: 2 })
+            .for_each(|(_, _, c)| *c = 'o');
+        assert_eq!(grid.to_string(), "1X3\nooX\n7o9\n");
+    }
+
+    #[test]
+    fn test_transpose() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n").unwrap();
+        assert_eq!(grid.transposed().to_string(), "14\n25\n36\n");
+
+        grid.transpose();
+        assert_eq!(grid.to_string(), "14\n25\n36\n");
+        assert_eq!(grid.columns, 2);
+        assert_eq!(grid.rows(), 3);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n").unwrap();
+        assert_eq!(grid.rotated_cw().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotated_ccw().to_string(), "36\n25\n14\n");
+
+        let original = grid.clone();
+        grid.rotate_cw();
+        assert_eq!(grid.to_string(), "41\n52\n63\n");
+        grid.rotate_cw();
+        assert_eq!(grid.to_string(), "654\n321\n");
+        grid.rotate_ccw();
+        grid.rotate_ccw();
+        assert_eq!(grid, original);
+    }
+
+    #[test]
+    fn test_flip() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n").unwrap();
+        assert_eq!(grid.flipped_horizontal().to_string(), "321\n654\n");
+        assert_eq!(grid.flipped_vertical().to_string(), "456\n123\n");
+
+        grid.flip_horizontal();
+        assert_eq!(grid.to_string(), "321\n654\n");
+        grid.flip_vertical();
+        assert_eq!(grid.to_string(), "654\n321\n");
+
+        let mut empty = Grid::<char>::new(vec![], 0);
+        empty.flip_horizontal();
+        empty.flip_vertical();
+        assert_eq!(empty, Grid::new(vec![], 0));
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid: Grid<char> = Grid::from_str("..#..\n.##..\n#...#\n").unwrap();
+
+        let mut filled = grid.flood_fill(&Position { x: 0, y: 0 }, |c| c == &'.');
+        filled.sort();
+        assert_eq!(
+            filled,
+            vec![
+                Position { x: 0, y: 0 },
+                Position { x: 0, y: 1 },
+                Position { x: 1, y: 0 },
+            ]
+        );
+
+        let filled = grid.flood_fill(&Position { x: 3, y: 0 }, |c| c == &'.');
+        assert_eq!(filled.len(), 7);
+
+        assert!(grid
+            .flood_fill(&Position { x: 2, y: 0 }, |c| c == &'.')
+            .is_empty());
+        assert!(grid
+            .flood_fill(&Position { x: 9, y: 0 }, |c| c == &'.')
+            .is_empty());
+    }
+
+    #[test]
+    fn test_at_wrapped() {
+        let mut grid: Grid<char> = Grid::from_str("123\n456\n").unwrap();
+
+        assert_eq!(
+            grid.at_wrapped(&IntPosition { x: 1, y: 1 }),
+            (&'5', IntPosition { x: 0, y: 0 })
+        );
+        assert_eq!(
+            grid.at_wrapped(&IntPosition { x: -1, y: -1 }),
+            (&'6', IntPosition { x: -1, y: -1 })
+        );
+        assert_eq!(
+            grid.wrap_position(&IntPosition { x: 7, y: -4 }),
+            (Position { x: 1, y: 0 }, IntPosition { x: 2, y: -2 })
+        );
+
+        *grid.at_wrapped_mut(&IntPosition { x: -3, y: 2 }).0 = 'x';
+        assert_eq!(grid.to_string(), "x23\n456\n");
+
+        let neighbors = grid
+            .neighbors4_wrapped(&IntPosition { x: 0, y: 0 })
+            .map(|(_, _, c)| c)
+            .collect::<String>();
+        assert_eq!(neighbors, "4243");
+    }
+
+    #[test]
+    fn test_neighbors4_wrapped_infinite_garden() {
+        let grid: Grid<char> = Grid::from_str(GARDEN).unwrap();
+        let start = IntPosition::from(grid.find_pos(|c| c == &'S').unwrap());
+
+        let reachable = |steps: usize| {
+            let mut frontier = std::collections::HashSet::from([start]);
+            for _ in 0..steps {
+                frontier = frontier
+                    .iter()
+                    .flat_map(|p| grid.neighbors4_wrapped(p))
+                    .filter(|(_, _, c)| c != &&'#')
+                    .map(|(_, p, _)| p)
+                    .collect();
+            }
+            frontier.len()
+        };
+
+        assert_eq!(reachable(6), 16);
+        assert_eq!(reachable(10), 50);
+        assert_eq!(reachable(50), 1594);
+    }
+
+    #[test]
+    fn test_label_regions() {
+        let grid: Grid<char> = Grid::from_str("#####\n#..##\n#####\n...#.\n").unwrap();
+        let (labels, regions) = grid.label_regions(|a, b| a == b);
+
+        assert_eq!(regions.len(), 4);
+        assert_eq!(labels.at(&Position { x: 0, y: 0 }), Some(&RegionId(0)));
+        assert_eq!(labels.at(&Position { x: 2, y: 1 }), Some(&RegionId(1)));
+        assert_eq!(labels.at(&Position { x: 1, y: 3 }), Some(&RegionId(2)));
+        assert_eq!(labels.at(&Position { x: 4, y: 3 }), Some(&RegionId(3)));
+
+        assert_eq!(
+            regions[1],
+            Region {
+                id: RegionId(1),
+                area: 2,
+                perimeter: 6,
+                min: Position { x: 1, y: 1 },
+                max: Position { x: 2, y: 1 },
+                touches_border: false,
+            }
+        );
+        assert_eq!(regions[0].area, 14);
+        assert_eq!(regions[0].max, Position { x: 4, y: 3 });
+        assert!(regions[0].touches_border);
+        assert_eq!(regions[2].perimeter, 8);
+        assert!(regions[3].touches_border);
+    }
+
     #[test]
     fn test_mut_find() {
         let mut grid: Grid<char> = Grid::from_str("123\n456\n789\n").unwrap();
@@ -0,0 +1,358 @@
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+use crate::grid::Grid;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash, PartialOrd, Ord)]
+pub struct Position3 {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+}
+
+impl Position3 {
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash, PartialOrd, Ord)]
+pub struct Vec3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Vec3 {
+    pub fn new(x: isize, y: isize, z: isize) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn dot(&self, other: &Vec3) -> isize {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn component_min(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    pub fn component_max(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+}
+
+impl From<Position3> for Vec3 {
+    fn from(value: Position3) -> Self {
+        Vec3::new(value.x as isize, value.y as isize, value.z as isize)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<isize> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, rhs: isize) -> Self::Output {
+        Vec3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+    fn neg(self) -> Self::Output {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+// axis aligned box, both corners are part of it like the bricks in 2023 day22
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Box3 {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Box3 {
+    pub fn new(a: Vec3, b: Vec3) -> Self {
+        Box3 {
+            min: a.component_min(&b),
+            max: a.component_max(&b),
+        }
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min + Vec3::new(1, 1, 1)
+    }
+
+    pub fn volume(&self) -> usize {
+        let size = self.size();
+        (size.x * size.y * size.z) as usize
+    }
+
+    pub fn contains(&self, pos: &Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x)
+            && (self.min.y..=self.max.y).contains(&pos.y)
+            && (self.min.z..=self.max.z).contains(&pos.z)
+    }
+
+    pub fn intersects(&self, other: &Box3) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // only looks at x and y, e.g. whether one brick can land on another
+    pub fn overlaps_xy(&self, other: &Box3) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn intersection(&self, other: &Box3) -> Option<Box3> {
+        let min = self.min.component_max(&other.min);
+        let max = self.max.component_min(&other.max);
+        (min.x <= max.x && min.y <= max.y && min.z <= max.z).then_some(Box3 { min, max })
+    }
+
+    pub fn translated(&self, offset: Vec3) -> Box3 {
+        Box3 {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+// x runs along a row, y along a column of a layer and z from layer to layer
+#[derive(Debug, PartialEq, Eq, Clone, Default, Hash)]
+pub struct Grid3<T> {
+    data: Vec<T>,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl<T> Grid3<T> {
+    pub fn new(data: Vec<T>, columns: usize, rows: usize) -> Self {
+        Grid3 {
+            data,
+            columns,
+            rows,
+        }
+    }
+
+    pub fn from_fn<F: FnMut(Position3) -> T>(
+        columns: usize,
+        rows: usize,
+        layers: usize,
+        mut f: F,
+    ) -> Self {
+        let data = (0..layers)
+            .flat_map(|z| (0..rows).flat_map(move |y| (0..columns).map(move |x| (x, y, z))))
+            .map(|(x, y, z)| f(Position3 { x, y, z }))
+            .collect();
+        Grid3::new(data, columns, rows)
+    }
+
+    fn layer_size(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    pub fn layers(&self) -> usize {
+        self.data.len().checked_div(self.layer_size()).unwrap_or(0)
+    }
+
+    fn position_to_index(&self, pos: &Position3) -> Option<usize> {
+        if pos.x >= self.columns || pos.y >= self.rows {
+            return None;
+        }
+        let offset = pos.z * self.layer_size() + pos.y * self.columns + pos.x;
+        (offset < self.data.len()).then_some(offset)
+    }
+
+    pub fn is_position_valid(&self, pos: &Position3) -> bool {
+        self.position_to_index(pos).is_some()
+    }
+
+    pub fn at(&self, pos: &Position3) -> Option<&T> {
+        self.position_to_index(pos).map(|i| &self.data[i])
+    }
+
+    pub fn at_mut(&mut self, pos: &Position3) -> Option<&mut T> {
+        self.position_to_index(pos).map(|i| &mut self.data[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut()
+    }
+
+    pub fn iter_layer(&self, z: usize) -> impl Iterator<Item = &T> {
+        if z >= self.layers() {
+            panic!("Out of bound layer index");
+        }
+        let layer_start = z * self.layer_size();
+        self.data[layer_start..layer_start + self.layer_size()].iter()
+    }
+
+    pub fn iter_layer_mut(&mut self, z: usize) -> impl Iterator<Item = &mut T> {
+        if z >= self.layers() {
+            panic!("Out of bound layer index");
+        }
+        let layer_start = z * self.layer_size();
+        let layer_end = layer_start + self.layer_size();
+        self.data[layer_start..layer_end].iter_mut()
+    }
+
+    // the cells above each other at (x, y), from the lowest layer up
+    pub fn iter_column(&self, x: usize, y: usize) -> impl Iterator<Item = &T> {
+        if x >= self.columns || y >= self.rows {
+            panic!("Out of bound column index");
+        }
+        self.data
+            .iter()
+            .skip(y * self.columns + x)
+            .step_by(self.layer_size())
+    }
+
+    pub fn iter_column_mut(&mut self, x: usize, y: usize) -> impl Iterator<Item = &mut T> {
+        if x >= self.columns || y >= self.rows {
+            panic!("Out of bound column index");
+        }
+        let layer_size = self.layer_size();
+        self.data
+            .iter_mut()
+            .skip(y * self.columns + x)
+            .step_by(layer_size)
+    }
+}
+
+impl<T: Clone> Grid3<T> {
+    pub fn filled(columns: usize, rows: usize, layers: usize, value: T) -> Self {
+        Grid3::new(vec![value; columns * rows * layers], columns, rows)
+    }
+
+    pub fn layer(&self, z: usize) -> Grid<T> {
+        Grid::new(self.iter_layer(z).cloned().collect(), self.columns)
+    }
+}
+
+impl<T> Index<Position3> for Grid3<T> {
+    type Output = T;
+    fn index(&self, pos: Position3) -> &Self::Output {
+        self.at(&pos).expect("Out of bound position")
+    }
+}
+
+impl<T> IndexMut<Position3> for Grid3<T> {
+    fn index_mut(&mut self, pos: Position3) -> &mut Self::Output {
+        self.at_mut(&pos).expect("Out of bound position")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vec3() {
+        let a = Vec3::new(1, 2, 3);
+        let b = Vec3::new(-2, 0, 5);
+        assert_eq!(a + b, Vec3::new(-1, 2, 8));
+        assert_eq!(a - b, Vec3::new(3, 2, -2));
+        assert_eq!(-a * 2, Vec3::new(-2, -4, -6));
+        assert_eq!(a.dot(&b), 13);
+        assert_eq!(
+            Vec3::new(1, 0, 0).cross(&Vec3::new(0, 1, 0)),
+            Vec3::new(0, 0, 1)
+        );
+        assert_eq!(a.manhattan_distance(&b), 7);
+        assert_eq!(
+            Position3 { x: 1, y: 2, z: 3 }.manhattan_distance(&Position3 { x: 3, y: 2, z: 0 }),
+            5
+        );
+    }
+
+    #[test]
+    fn test_box3() {
+        // bricks A and B of the 2023 day22 example
+        let a = Box3::new(Vec3::new(1, 0, 1), Vec3::new(1, 2, 1));
+        let b = Box3::new(Vec3::new(2, 0, 2), Vec3::new(0, 0, 2));
+
+        assert_eq!(a.size(), Vec3::new(1, 3, 1));
+        assert_eq!(b.volume(), 3);
+        assert!(a.contains(&Vec3::new(1, 1, 1)));
+        assert!(!a.contains(&Vec3::new(1, 3, 1)));
+
+        assert!(!a.intersects(&b));
+        assert!(a.overlaps_xy(&b));
+        let fallen = b.translated(Vec3::new(0, 0, -1));
+        assert_eq!(
+            a.intersection(&fallen),
+            Some(Box3::new(Vec3::new(1, 0, 1), Vec3::new(1, 0, 1)))
+        );
+    }
+
+    #[test]
+    fn test_grid3() {
+        let mut grid = Grid3::from_fn(3, 2, 2, |p| p.x + 10 * p.y + 100 * p.z);
+
+        assert_eq!(grid.layers(), 2);
+        assert_eq!(grid.at(&Position3 { x: 2, y: 1, z: 1 }), Some(&112));
+        assert_eq!(grid.at(&Position3 { x: 3, y: 0, z: 0 }), None);
+        assert_eq!(grid.at(&Position3 { x: 0, y: 0, z: 2 }), None);
+
+        assert_eq!(
+            grid.iter_column(1, 1).copied().collect::<Vec<_>>(),
+            vec![11, 111]
+        );
+        assert_eq!(
+            grid.iter_layer(1).copied().collect::<Vec<_>>(),
+            vec![100, 101, 102, 110, 111, 112]
+        );
+        assert_eq!(grid.layer(0).rows(), 2);
+
+        grid.iter_column_mut(0, 0).for_each(|c| *c += 1000);
+        grid.iter_layer_mut(0).for_each(|c| *c += 1);
+        assert_eq!(grid[Position3 { x: 0, y: 0, z: 0 }], 1001);
+        assert_eq!(grid[Position3 { x: 0, y: 0, z: 1 }], 1100);
+        grid[Position3 { x: 1, y: 0, z: 1 }] = 7;
+        assert_eq!(
+            grid.layer(1).at(&crate::grid::Position { x: 1, y: 0 }),
+            Some(&7)
+        );
+
+        let filled = Grid3::filled(2, 2, 3, false);
+        assert_eq!(filled.layers(), 3);
+        assert_eq!(filled.iter().filter(|c| !**c).count(), 12);
+    }
+}
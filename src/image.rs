@@ -0,0 +1,104 @@
+use std::io::{self, Write};
+
+use crate::grid::Grid;
+use crate::render::Color;
+
+impl<T> Grid<T> {
+    // every cell becomes a `scale` x `scale` block of pixels
+    fn pixels<F: Fn(&T) -> Color>(&self, scale: usize, color: F) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.iter().count() * scale * scale * 3);
+        for row in self.iter_rows() {
+            let line = row
+                .iter()
+                .flat_map(|t| {
+                    let (r, g, b) = color(t).rgb();
+                    [r, g, b].repeat(scale)
+                })
+                .collect::<Vec<_>>();
+            (0..scale).for_each(|_| pixels.extend_from_slice(&line));
+        }
+        pixels
+    }
+
+    pub fn write_ppm<W: Write, F: Fn(&T) -> Color>(
+        &self,
+        mut writer: W,
+        scale: usize,
+        color: F,
+    ) -> io::Result<()> {
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.columns * scale,
+            self.rows() * scale
+        )?;
+        writer.write_all(&self.pixels(scale, color))
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png<W: Write, F: Fn(&T) -> Color>(
+        &self,
+        writer: W,
+        scale: usize,
+        color: F,
+    ) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(
+            writer,
+            (self.columns * scale) as u32,
+            (self.rows() * scale) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut w| w.write_image_data(&self.pixels(scale, color)))
+            .map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn color(c: &char) -> Color {
+        match c {
+            '#' => Color::Rgb(255, 0, 0),
+            _ => Color::Black,
+        }
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let grid: Grid<char> = Grid::from_str("#.\n..\n.#\n").unwrap();
+        let mut ppm = vec![];
+        grid.write_ppm(&mut ppm, 2, color).unwrap();
+
+        let header = b"P6\n4 6\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 4 * 6 * 3);
+        assert_eq!(&pixels[..12], &[255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&pixels[12..24], &pixels[..12]);
+        assert_eq!(&pixels[pixels.len() - 6..], &[255, 0, 0, 255, 0, 0]);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_write_png() {
+        let grid: Grid<char> = Grid::from_str("#.\n..\n.#\n").unwrap();
+        let mut encoded = vec![];
+        grid.write_png(&mut encoded, 3, color).unwrap();
+
+        let mut reader = png::Decoder::new(io::Cursor::new(encoded))
+            .read_info()
+            .unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!((info.width, info.height), (6, 9));
+        assert_eq!(&pixels[..3], &[255, 0, 0]);
+        assert_eq!(&pixels[9..12], &[0, 0, 0]);
+    }
+}
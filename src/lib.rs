@@ -3,7 +3,14 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+pub mod bitgrid;
+pub mod compress;
+pub mod cycle;
 pub mod grid;
+pub mod grid3;
+pub mod image;
+pub mod render;
+pub mod search;
 
 pub mod day1;
 pub mod day2;
@@ -0,0 +1,218 @@
+use core::fmt;
+use std::collections::HashSet;
+use std::io::IsTerminal;
+
+use crate::grid::{Grid, Position};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Color::Black => (0, 0, 0),
+            Color::Red => (205, 49, 49),
+            Color::Green => (13, 188, 121),
+            Color::Yellow => (229, 229, 16),
+            Color::Blue => (36, 114, 200),
+            Color::Magenta => (188, 63, 188),
+            Color::Cyan => (17, 168, 205),
+            Color::White => (229, 229, 229),
+            Color::Rgb(r, g, b) => (*r, *g, *b),
+        }
+    }
+
+    fn code(&self, base: u8) -> String {
+        match self {
+            Color::Black => base.to_string(),
+            Color::Red => (base + 1).to_string(),
+            Color::Green => (base + 2).to_string(),
+            Color::Yellow => (base + 3).to_string(),
+            Color::Blue => (base + 4).to_string(),
+            Color::Magenta => (base + 5).to_string(),
+            Color::Cyan => (base + 6).to_string(),
+            Color::White => (base + 7).to_string(),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Self {
+        Style {
+            fg: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn bg(color: Color) -> Self {
+        Style {
+            bg: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Style { bold: true, ..self }
+    }
+
+    // fields set in `other` win
+    fn merge(self, other: &Style) -> Style {
+        Style {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+            bold: self.bold || other.bold,
+        }
+    }
+
+    fn escape_sequence(&self) -> String {
+        let codes = self
+            .bold
+            .then(|| "1".to_string())
+            .into_iter()
+            .chain(self.fg.map(|c| c.code(30)))
+            .chain(self.bg.map(|c| c.code(40)))
+            .collect::<Vec<_>>();
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+type StyleFn<'a, T> = Box<dyn Fn(&Position, &T) -> Style + 'a>;
+
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    style: Option<StyleFn<'a, T>>,
+    overlays: Vec<(HashSet<Position>, Style)>,
+    ansi: bool,
+}
+
+impl<T> Grid<T> {
+    // colours are only emitted when stdout is a terminal unless overridden with `Renderer::ansi`
+    pub fn render(&self) -> Renderer<'_, T> {
+        Renderer {
+            grid: self,
+            style: None,
+            overlays: vec![],
+            ansi: std::io::stdout().is_terminal(),
+        }
+    }
+}
+
+impl<'a, T> Renderer<'a, T> {
+    pub fn style<F: Fn(&Position, &T) -> Style + 'a>(mut self, style: F) -> Self {
+        self.style = Some(Box::new(style));
+        self
+    }
+
+    // overlays are drawn on top of the cell style in the order they were added
+    pub fn overlay<I: IntoIterator<Item = Position>>(mut self, positions: I, style: Style) -> Self {
+        self.overlays.push((positions.into_iter().collect(), style));
+        self
+    }
+
+    pub fn ansi(mut self, enabled: bool) -> Self {
+        self.ansi = enabled;
+        self
+    }
+
+    fn cell_style(&self, pos: &Position, t: &T) -> Style {
+        let style = self.style.as_ref().map(|f| f(pos, t)).unwrap_or_default();
+        self.overlays
+            .iter()
+            .filter(|(positions, _)| positions.contains(pos))
+            .fold(style, |style, (_, overlay)| style.merge(overlay))
+    }
+}
+
+impl<T: Into<char> + Clone> fmt::Display for Renderer<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.grid.iter_rows().enumerate() {
+            let mut current = Style::default();
+            for (x, t) in row.iter().enumerate() {
+                let c: char = t.clone().into();
+                if !self.ansi {
+                    write!(f, "{}", c)?;
+                    continue;
+                }
+
+                let style = self.cell_style(&Position { x, y }, t);
+                if style != current {
+                    if current != Style::default() {
+                        write!(f, "\x1b[0m")?;
+                    }
+                    if style != Style::default() {
+                        write!(f, "{}", style.escape_sequence())?;
+                    }
+                    current = style;
+                }
+                write!(f, "{}", c)?;
+            }
+            if current != Style::default() {
+                write!(f, "\x1b[0m")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_render_plain() {
+        let grid: Grid<char> = Grid::from_str("#..\n.#.\n..#\n").unwrap();
+        let rendered = grid
+            .render()
+            .style(|_, c| Style::fg(if c == &'#' { Color::Red } else { Color::Blue }))
+            .overlay([Position { x: 1, y: 0 }], Style::bg(Color::Yellow))
+            .ansi(false)
+            .to_string();
+
+        assert_eq!(rendered, grid.to_string());
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let grid: Grid<char> = Grid::from_str("#..\n.#.\n").unwrap();
+        let rendered = grid
+            .render()
+            .style(|_, c| {
+                if c == &'#' {
+                    Style::fg(Color::Red).bold()
+                } else {
+                    Style::default()
+                }
+            })
+            .overlay(
+                [Position { x: 1, y: 0 }, Position { x: 2, y: 0 }],
+                Style::bg(Color::Rgb(1, 2, 3)),
+            )
+            .ansi(true)
+            .to_string();
+
+        assert_eq!(
+            rendered,
+            "\x1b[1;31m#\x1b[0m\x1b[48;2;1;2;3m..\x1b[0m\n.\x1b[1;31m#\x1b[0m.\n"
+        );
+    }
+}
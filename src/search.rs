@@ -0,0 +1,331 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::grid::{Direction, Grid, Position};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Path<S> {
+    pub cost: usize,
+    pub states: Vec<S>,
+}
+
+pub fn bfs<S, FN, IN, FG>(start: S, mut successors: FN, mut is_goal: FG) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = S>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut states = vec![start.clone()];
+    let mut parents = vec![None];
+    let mut index = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(i) = queue.pop_front() {
+        if is_goal(&states[i]) {
+            let states = reconstruct_path(&states, &parents, i);
+            return Some(Path {
+                cost: states.len() - 1,
+                states,
+            });
+        }
+        for next in successors(&states[i]) {
+            if let Entry::Vacant(e) = index.entry(next) {
+                let j = states.len();
+                states.push(e.key().clone());
+                parents.push(Some(i));
+                e.insert(j);
+                queue.push_back(j);
+            }
+        }
+    }
+    None
+}
+
+pub fn dijkstra<S, FN, IN, FG>(start: S, successors: FN, is_goal: FG) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, usize)>,
+    FG: FnMut(&S) -> bool,
+{
+    astar(start, successors, |_| 0, is_goal)
+}
+
+// `heuristic` must never overestimate the remaining cost, otherwise the returned path may not be the cheapest
+pub fn astar<S, FN, IN, FH, FG>(
+    start: S,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut is_goal: FG,
+) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, usize)>,
+    FH: FnMut(&S) -> usize,
+    FG: FnMut(&S) -> bool,
+{
+    // states are interned, the heap and the parent links only deal with indices
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut states = vec![start.clone()];
+    let mut parents = vec![None];
+    let mut costs = vec![0];
+    let mut index = HashMap::from([(start, 0)]);
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > costs[i] {
+            continue;
+        }
+        if is_goal(&states[i]) {
+            return Some(Path {
+                cost,
+                states: reconstruct_path(&states, &parents, i),
+            });
+        }
+        for (next, step_cost) in successors(&states[i]) {
+            let next_cost = cost + step_cost;
+            let j = match index.entry(next) {
+                Entry::Vacant(e) => {
+                    let j = states.len();
+                    states.push(e.key().clone());
+                    parents.push(Some(i));
+                    costs.push(next_cost);
+                    e.insert(j);
+                    j
+                }
+                Entry::Occupied(e) => {
+                    let j = *e.get();
+                    if next_cost >= costs[j] {
+                        continue;
+                    }
+                    parents[j] = Some(i);
+                    costs[j] = next_cost;
+                    j
+                }
+            };
+            heap.push(Reverse((next_cost + heuristic(&states[j]), next_cost, j)));
+        }
+    }
+    None
+}
+
+fn reconstruct_path<S: Clone>(states: &[S], parents: &[Option<usize>], end: usize) -> Vec<S> {
+    let mut path = std::iter::successors(Some(end), |i| parents[*i])
+        .map(|i| states[i].clone())
+        .collect::<Vec<_>>();
+    path.reverse();
+    path
+}
+
+// state of a walk over a grid, `straight` counts the steps taken in `direction` so far
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct GridState {
+    pub position: Position,
+    pub direction: Option<Direction>,
+    pub straight: usize,
+}
+
+impl GridState {
+    pub fn new(position: Position) -> Self {
+        GridState {
+            position,
+            direction: None,
+            straight: 0,
+        }
+    }
+}
+
+// `cost` gets the current state, the direction of the move and the entered cell, `None` forbids the move
+pub fn grid_dijkstra<T, FC, FG>(
+    grid: &Grid<T>,
+    start: Position,
+    is_goal: FG,
+    mut cost: FC,
+) -> Option<Path<GridState>>
+where
+    FC: FnMut(&GridState, Direction, &T) -> Option<usize>,
+    FG: FnMut(&GridState) -> bool,
+{
+    dijkstra(
+        GridState::new(start),
+        |state| {
+            grid.neighbors4(&state.position)
+                .filter_map(|(direction, position, t)| {
+                    let step_cost = cost(state, direction, t)?;
+                    let straight = if state.direction == Some(direction) {
+                        state.straight + 1
+                    } else {
+                        1
+                    };
+                    Some((
+                        GridState {
+                            position,
+                            direction: Some(direction),
+                            straight,
+                        },
+                        step_cost,
+                    ))
+                })
+                .collect::<Vec<_>>()
+        },
+        is_goal,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn graph_successors(n: &usize) -> Vec<(usize, usize)> {
+        // 0 -> 1 -> 3 is cheaper than the direct 0 -> 3 edge
+        match n {
+            0 => vec![(1, 1), (2, 4), (3, 10)],
+            1 => vec![(3, 2)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs(
+            0,
+            |n| graph_successors(n).into_iter().map(|(n, _)| n),
+            |n| *n == 3,
+        )
+        .unwrap();
+        assert_eq!(path.cost, 1);
+        assert_eq!(path.states, vec![0, 3]);
+
+        assert_eq!(
+            bfs(
+                3,
+                |n| graph_successors(n).into_iter().map(|(n, _)| n),
+                |n| *n == 0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let path = dijkstra(0, graph_successors, |n| *n == 3).unwrap();
+        assert_eq!(path.cost, 3);
+        assert_eq!(path.states, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn test_astar() {
+        let goal = (5isize, 3isize);
+        let path = astar(
+            (0isize, 0isize),
+            |&(x, y)| {
+                [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                    .into_iter()
+                    .filter(|(x, y)| (0..10).contains(x) && (0..10).contains(y) && *x != 2)
+                    .map(|p| (p, 1))
+                    .collect::<Vec<_>>()
+            },
+            |&(x, y)| x.abs_diff(goal.0) + y.abs_diff(goal.1),
+            |p| *p == goal,
+        );
+        assert_eq!(path, None);
+
+        let path = astar(
+            (0isize, 0isize),
+            |&(x, y)| {
+                [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                    .into_iter()
+                    .filter(|(x, y)| {
+                        (0..10).contains(x) && (0..10).contains(y) && (*x, *y) != (2, 0)
+                    })
+                    .map(|p| (p, 1))
+                    .collect::<Vec<_>>()
+            },
+            |&(x, y)| x.abs_diff(goal.0) + y.abs_diff(goal.1),
+            |p| *p == goal,
+        )
+        .unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.states.first(), Some(&(0, 0)));
+        assert_eq!(path.states.last(), Some(&goal));
+    }
+
+    #[test]
+    fn test_grid_dijkstra() {
+        let grid: Grid<char> = Grid::from_str("..#.\n.##.\n....\n").unwrap();
+        let goal = Position { x: 3, y: 0 };
+
+        let path = grid_dijkstra(
+            &grid,
+            Position { x: 0, y: 0 },
+            |s| s.position == goal,
+            |_, _, c| (c != &'#').then_some(1),
+        )
+        .unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.states.len(), 8);
+
+        // never turn around and never walk more than two steps in a row
+        let path = grid_dijkstra(
+            &grid,
+            Position { x: 0, y: 0 },
+            |s| s.position == goal,
+            |s, d, c| {
+                let allowed = c != &'#'
+                    && s.direction != Some(d.invert())
+                    && (s.direction != Some(d) || s.straight < 2);
+                allowed.then_some(1)
+            },
+        );
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn test_grid_dijkstra_crucible() {
+        let grid: Grid<usize> = Grid::new(
+            "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533"
+                .lines()
+                .flat_map(|l| l.chars().map(|c| c.to_digit(10).unwrap() as usize))
+                .collect(),
+            13,
+        );
+        let goal = Position { x: 12, y: 12 };
+
+        let heat_loss = |min_straight: usize, max_straight: usize| {
+            grid_dijkstra(
+                &grid,
+                Position { x: 0, y: 0 },
+                |s| s.position == goal && s.straight >= min_straight,
+                |s, dir, heat_loss| match s.direction {
+                    None => Some(*heat_loss),
+                    Some(d) if d == dir.invert() => None,
+                    Some(d) if d == dir => (s.straight < max_straight).then_some(*heat_loss),
+                    Some(_) => (s.straight >= min_straight).then_some(*heat_loss),
+                },
+            )
+            .unwrap()
+            .cost
+        };
+
+        assert_eq!(heat_loss(0, 3), 102);
+        assert_eq!(heat_loss(4, 10), 94);
+    }
+}